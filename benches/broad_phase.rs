@@ -0,0 +1,85 @@
+//! Compares the brute force broad phase with the spatial grid in a crowded arena:
+//! 10k enemies and 200 players, for both collision checks and vision queries.
+
+use creative_coding::{
+    config::{ArenaConfig, WorldConfig},
+    grid::BroadPhase,
+    world::{Enemy, Player, Position, World},
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use nannou::{
+    geom::Vec2,
+    rand::{rngs::StdRng, Rng, SeedableRng},
+};
+
+const ENEMIES: usize = 10_000;
+const PLAYERS: usize = 200;
+const ARENA_SIZE: f32 = 2048.;
+
+/// A large arena with players and enemies scattered uniformly across it.
+fn crowded_world(broad_phase: BroadPhase) -> World {
+    let mut rng = StdRng::seed_from_u64(0);
+    let half: f32 = ARENA_SIZE / 2.;
+    let mut random_position = || Position {
+        x: rng.gen_range(-half..half),
+        y: rng.gen_range(-half..half),
+    };
+    let players: Vec<Player> = (0..PLAYERS)
+        .map(|_| Player {position: random_position(), ..Default::default()})
+        .collect();
+    let enemies: Vec<Enemy> = (0..ENEMIES)
+        .map(|_| Enemy {position: random_position(), ..Default::default()})
+        .collect();
+    let config = WorldConfig {
+        enemy_count: 0,
+        arena: ArenaConfig {width: ARENA_SIZE, height: ARENA_SIZE},
+        ..Default::default()
+    };
+    // setting up the world puts every player in the middle, so scatter them afterwards
+    let mut world = World::new(config, 0);
+    world.players = players;
+    world.enemies = enemies;
+    world.broad_phase = broad_phase;
+    world.broad_phase.rebuild(world.enemies.iter().map(|enemy| (enemy.position.into(), enemy.radius)));
+    world
+}
+
+fn broad_phases() -> [(&'static str, BroadPhase); 2] {
+    [("brute_force", BroadPhase::BruteForce), ("grid", BroadPhase::default())]
+}
+
+fn collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collisions");
+    for (name, broad_phase) in broad_phases() {
+        let mut world = crowded_world(broad_phase);
+        group.bench_function(name, |b| b.iter(|| {
+            // revive everyone so every iteration checks every player
+            for player in world.players.iter_mut() {
+                player.alive = true;
+            }
+            world.detect_collisions();
+        }));
+    }
+    group.finish();
+}
+
+fn vision(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vision");
+    group.sample_size(10);
+    for (name, broad_phase) in broad_phases() {
+        let world = crowded_world(broad_phase);
+        group.bench_function(name, |b| b.iter(|| {
+            for player in world.players.iter() {
+                let origin: Vec2 = player.position.into();
+                let visible = world.broad_phase
+                    .near(origin, player.vision.range, world.enemies.len())
+                    .map(|index| &world.enemies[index]);
+                black_box(player.vision.look(origin, player.heading, visible, &world.space(), &world.obstacles, &world.food));
+            }
+        }));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, collisions, vision);
+criterion_main!(benches);
@@ -0,0 +1,155 @@
+//! What happens at the edge of the arena.
+//!
+//! Every entity type has its own [`Boundary`]: clamped against the wall, wrapped around to the
+//! opposite edge, bounced back, or killed on contact. When players wrap around, the arena is a
+//! torus as far as they're concerned, and a [`Space`] measures distances the short way around so
+//! collisions and vision work across the edges too.
+
+use nannou::geom::{Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::world::Position;
+
+/// How an entity is kept within the arena.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Boundary {
+	/// Stops at the wall, losing the part of its velocity that points into it.
+	#[default]
+	Clamp,
+	/// Leaves through one edge and comes back in through the opposite one.
+	Wrap,
+	/// Is reflected off the wall, keeping its speed.
+	Bounce,
+	/// Dies on touching the wall.
+	Lethal,
+}
+
+impl Boundary {
+	/// Brings an entity that left the arena back in.
+	///
+	/// Arguments
+	/// * `bounds`: the arena rectangle.
+	/// * `position`: the entity's position.
+	/// * `velocity`: the entity's velocity.
+	///
+	/// Returns
+	/// * `killed`: whether the entity touched a lethal wall.
+	pub fn confine(self, bounds: &Rect, position: &mut Position, velocity: &mut Vec2) -> bool {
+		match self {
+			Boundary::Clamp => {
+				clamp(bounds, position, velocity);
+				false
+			}
+			Boundary::Wrap => {
+				position.x = bounds.left() + (position.x - bounds.left()).rem_euclid(bounds.w());
+				position.y = bounds.bottom() + (position.y - bounds.bottom()).rem_euclid(bounds.h());
+				false
+			}
+			Boundary::Bounce => {
+				// mirror whatever went past the wall back inside, then clamp in case it went past twice
+				let (left, right, bottom, top) = (bounds.left(), bounds.right(), bounds.bottom(), bounds.top());
+				if position.x < left || position.x > right {
+					position.x = 2. * position.x.clamp(left, right) - position.x;
+					velocity.x = -velocity.x;
+				}
+				if position.y < bottom || position.y > top {
+					position.y = 2. * position.y.clamp(bottom, top) - position.y;
+					velocity.y = -velocity.y;
+				}
+				position.x = position.x.clamp(left, right);
+				position.y = position.y.clamp(bottom, top);
+				false
+			}
+			Boundary::Lethal => {
+				let touched: bool = position.x <= bounds.left()
+					|| position.x >= bounds.right()
+					|| position.y <= bounds.bottom()
+					|| position.y >= bounds.top();
+				clamp(bounds, position, velocity);
+				touched
+			}
+		}
+	}
+}
+
+/// Stops an entity at the wall.
+fn clamp(bounds: &Rect, position: &mut Position, velocity: &mut Vec2) {
+	if position.y > bounds.top() {
+		position.y = bounds.top();
+		velocity.y = velocity.y.min(0.);
+	}
+	if position.y < bounds.bottom() {
+		position.y = bounds.bottom();
+		velocity.y = velocity.y.max(0.);
+	}
+	if position.x < bounds.left() {
+		position.x = bounds.left();
+		velocity.x = velocity.x.max(0.);
+	}
+	if position.x > bounds.right() {
+		position.x = bounds.right();
+		velocity.x = velocity.x.min(0.);
+	}
+}
+
+/// The arena as the players perceive it: a walled rectangle, or a torus without walls.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Space {
+	pub bounds: Rect,
+	/// Whether opposite edges are joined, so distances are measured the short way around.
+	pub wrap: bool,
+}
+
+impl Space {
+	/// The shortest displacement from one point to another.
+	pub fn offset(&self, from: Vec2, to: Vec2) -> Vec2 {
+		let offset: Vec2 = to - from;
+		match self.wrap {
+			true => Vec2::new(wrapped(offset.x, self.bounds.w()), wrapped(offset.y, self.bounds.h())),
+			false => offset,
+		}
+	}
+
+	/// Where `to` appears from `from`: the copy of it closest to `from`.
+	pub fn nearest(&self, from: Vec2, to: Vec2) -> Vec2 {
+		from + self.offset(from, to)
+	}
+
+	/// The shortest distance between two points.
+	pub fn distance(&self, from: Vec2, to: Vec2) -> f32 {
+		self.offset(from, to).length()
+	}
+
+	/// Centers of every copy of a circular area that overlaps the arena, starting with the area
+	/// itself. On a torus an area hanging over an edge shows up again on the opposite side.
+	///
+	/// Arguments
+	/// * `center`, `radius`: the area.
+	pub fn images(&self, center: Vec2, radius: f32) -> Vec<Vec2> {
+		if !self.wrap {
+			return vec![center];
+		}
+		let shifts = |position: f32, low: f32, high: f32, size: f32| {
+			let mut shifts: Vec<f32> = vec![0.];
+			if position - radius < low {
+				shifts.push(size);
+			}
+			if position + radius > high {
+				shifts.push(-size);
+			}
+			shifts
+		};
+		let Space {bounds, ..} = self;
+		let xs: Vec<f32> = shifts(center.x, bounds.left(), bounds.right(), bounds.w());
+		let ys: Vec<f32> = shifts(center.y, bounds.bottom(), bounds.top(), bounds.h());
+		xs.iter()
+			.flat_map(|x| ys.iter().map(move |y| center + Vec2::new(*x, *y)))
+			.collect()
+	}
+}
+
+/// Folds a coordinate difference into `[-size / 2, size / 2]`.
+fn wrapped(delta: f32, size: f32) -> f32 {
+	delta - size * (delta / size).round()
+}
@@ -0,0 +1,101 @@
+//! Where the front-end looks at the world from.
+//!
+//! The world lives in its own coordinates, set by the arena, while the window has pixels that
+//! change whenever it is resized. A [`Camera`] maps one onto the other: at a zoom of `1` the arena
+//! just fits the window, and panning and zooming move around the world without the simulation
+//! ever noticing.
+
+use nannou::{
+	geom::{Rect, Vec2},
+	Draw,
+};
+
+/// How far the camera can zoom out, relative to fitting the arena in the window.
+pub const MIN_ZOOM: f32 = 0.25;
+
+/// How far the camera can zoom in, relative to fitting the arena in the window.
+pub const MAX_ZOOM: f32 = 32.;
+
+/// A view of the world, framing `area` in the window at a zoom of `1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+	/// The part of the world that fits the window at a zoom of `1`, usually the arena.
+	pub area: Rect,
+	/// The world position shown at the center of the window.
+	pub center: Vec2,
+	pub zoom: f32,
+}
+
+impl Camera {
+	/// A camera framing an area of the world.
+	///
+	/// Arguments
+	/// * `area`: what should fit the window, usually the arena.
+	pub fn new(area: Rect) -> Self {
+		Self {area, center: area.xy(), zoom: 1.}
+	}
+
+	/// Frames the whole area again.
+	pub fn reset(&mut self) {
+		*self = Self::new(self.area);
+	}
+
+	/// Window pixels per world unit.
+	///
+	/// Arguments
+	/// * `window`: the window's rectangle.
+	pub fn scale(&self, window: &Rect) -> f32 {
+		let fit: f32 = (window.w() / self.area.w()).min(window.h() / self.area.h());
+		fit * self.zoom
+	}
+
+	/// Converts a point in window coordinates, like the mouse's, to world coordinates.
+	///
+	/// Arguments
+	/// * `window`: the window's rectangle.
+	/// * `point`: the point in the window.
+	pub fn to_world(&self, window: &Rect, point: Vec2) -> Vec2 {
+		self.center + (point - window.xy()) / self.scale(window)
+	}
+
+	/// Converts a point in world coordinates to window coordinates.
+	///
+	/// Arguments
+	/// * `window`: the window's rectangle.
+	/// * `point`: the point in the world.
+	pub fn to_window(&self, window: &Rect, point: Vec2) -> Vec2 {
+		window.xy() + (point - self.center) * self.scale(window)
+	}
+
+	/// A drawing context in world coordinates, for drawing the world through the camera.
+	///
+	/// Arguments
+	/// * `draw`: nannou::draw instance, in window coordinates.
+	/// * `window`: the window's rectangle.
+	pub fn transform(&self, draw: &Draw, window: &Rect) -> Draw {
+		draw.xy(window.xy())
+			.scale(self.scale(window))
+			.xy(-self.center)
+	}
+
+	/// Moves the camera along with something dragged across the window.
+	///
+	/// Arguments
+	/// * `window`: the window's rectangle.
+	/// * `delta`: how far it was dragged, in window pixels.
+	pub fn pan(&mut self, window: &Rect, delta: Vec2) {
+		self.center -= delta / self.scale(window);
+	}
+
+	/// Zooms in or out while keeping a point of the window over the same spot of the world.
+	///
+	/// Arguments
+	/// * `window`: the window's rectangle.
+	/// * `point`: the fixed point in window coordinates, e.g. the mouse.
+	/// * `factor`: how much to zoom in, below `1` to zoom out.
+	pub fn zoom_at(&mut self, window: &Rect, point: Vec2, factor: f32) {
+		let anchor: Vec2 = self.to_world(window, point);
+		self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+		self.center += anchor - self.to_world(window, point);
+	}
+}
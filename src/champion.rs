@@ -0,0 +1,117 @@
+//! Evolved brains saved to disk, so they can be shared and put back to work later.
+//!
+//! A [`Champion`] file holds a genome together with everything needed to rebuild its brain (the
+//! topology and what the player sees) and where it came from: its fitness, the generation and
+//! seed of the run, and a hash of the run's config. Files carry a format version, and files of a
+//! version this build doesn't know are refused rather than misread.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config::Config,
+	ga::Genome,
+	nn::{Network, Topology},
+	storage::{self, StorageError},
+	vision::Vision,
+	world::{Player, BRAIN_OUTPUTS},
+};
+
+/// Version of the champion file format written by this build.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A saved genome and the metadata describing it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Champion {
+	/// Format version the file was written in.
+	pub version: u32,
+	/// Shape of the brain the genes encode.
+	pub topology: Topology,
+	/// What the player sees, which decides the meaning of the brain's inputs.
+	pub vision: Vision,
+	pub genome: Genome,
+	/// Number of generations bred before the genome's own.
+	pub generation: usize,
+	/// Seed of the training run.
+	pub seed: u64,
+	/// [`Config::hash`] of the training run's config, as hexadecimal.
+	pub config_hash: String,
+}
+
+impl Champion {
+	/// Describes a genome evolved by a training run.
+	///
+	/// Arguments
+	/// * `genome`: the evaluated genome.
+	/// * `config`: the run's config, which decides the shape of the brain.
+	/// * `generation`: number of generations bred before the genome's own.
+	/// * `seed`: seed of the run.
+	pub fn new(genome: Genome, config: &Config, generation: usize, seed: u64) -> Self {
+		Self {
+			version: FORMAT_VERSION,
+			topology: config.ga.topology(),
+			vision: config.ga.vision,
+			genome,
+			generation,
+			seed,
+			config_hash: format!("{:016x}", config.hash()),
+		}
+	}
+
+	/// Whether the champion was evolved with the given config, up to its seed.
+	pub fn trained_with(&self, config: &Config) -> bool {
+		self.config_hash == format!("{:016x}", config.hash())
+	}
+
+	/// The brain the genome encodes.
+	pub fn network(&self) -> Network {
+		self.genome.brain(&self.topology)
+	}
+
+	/// A fresh player steered by the champion's brain and seeing what it was trained to see.
+	pub fn player(&self) -> Player {
+		Player {vision: self.vision, brain: Some(self.network()), ..Default::default()}
+	}
+
+	/// Reads a champion from a file, refusing unknown format versions, topologies no brain can have
+	/// and genomes that don't fit their topology.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+		let champion: Champion = storage::read_versioned_json(path, FORMAT_VERSION)?;
+		let (genes, weights) = (champion.genome.genes.len(), champion.topology.weight_count());
+		if genes != weights {
+			return Err(StorageError::Invalid(format!("{} genes for a brain of {} weights", genes, weights)));
+		}
+		let layers: &[usize] = &champion.topology.layers;
+		if layers.len() < 2 {
+			return Err(StorageError::Invalid("a brain needs at least an input and an output layer".to_string()));
+		}
+		if layers.first() != Some(&champion.vision.input_len()) || layers.last() != Some(&BRAIN_OUTPUTS) {
+			return Err(StorageError::Invalid("the brain doesn't fit a player's senses and steering".to_string()));
+		}
+		Ok(champion)
+	}
+
+	/// Writes the champion to a file.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StorageError> {
+		storage::write_json(path, self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn brain_with_a_single_layer_is_refused() {
+		let mut config = Config::default();
+		config.ga.vision = Vision {rays: 1, food: false, ..Vision::default()};
+		let mut champion = Champion::new(Genome::new(Vec::new()), &config, 0, 1);
+		champion.topology.layers = vec![BRAIN_OUTPUTS];
+		let path = std::env::temp_dir().join(format!("champion-test-{}.json", std::process::id()));
+		champion.save(&path).unwrap();
+		let loaded = Champion::load(&path);
+		std::fs::remove_file(&path).unwrap();
+		assert!(matches!(loaded, Err(StorageError::Invalid(_))));
+	}
+}
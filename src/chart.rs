@@ -0,0 +1,83 @@
+//! A line chart of how fitness evolves over the generations of a training run.
+//!
+//! [`draw_fitness_chart`] plots the best and mean fitness of the latest generations from the
+//! trainer's history in the bottom right corner of the window. Older generations scroll off the
+//! left edge, so the chart shows whether the run is still improving or has flattened out.
+
+use nannou::{
+	color::Rgba,
+	geom::{Rect, Vec2},
+	Draw,
+};
+
+use crate::{
+	panel::{draw_panel, Corner, LABEL_HEIGHT, MARGIN},
+	stats::GenerationStats,
+};
+
+/// Number of generations the chart shows at most.
+pub const CHART_GENERATIONS: usize = 100;
+
+/// Size of the chart panel.
+const PANEL_WIDTH: f32 = 240.;
+const PANEL_HEIGHT: f32 = 140.;
+
+/// Draws the best and mean fitness of the latest generations on a translucent panel.
+/// Nothing is drawn before the first generation has been evaluated.
+///
+/// Arguments
+/// * `draw`: nannou::draw instance.
+/// * `window`: the window's rectangle.
+/// * `history`: the statistics of every generation so far, oldest first.
+pub fn draw_fitness_chart(draw: &Draw, window: &Rect, history: &[GenerationStats]) {
+	if history.is_empty() {
+		return;
+	}
+	let (best_color, mean_color) = (Rgba::new(1.0, 0.85, 0.3, 1.0), Rgba::new(0.4, 0.8, 1.0, 1.0));
+	let shown: &[GenerationStats] = &history[history.len().saturating_sub(CHART_GENERATIONS)..];
+	let panel: Rect = draw_panel(draw, window, Corner::BottomRight, PANEL_WIDTH, PANEL_HEIGHT);
+	let plot = Rect::from_w_h(PANEL_WIDTH - MARGIN * 2., PANEL_HEIGHT - MARGIN * 2. - LABEL_HEIGHT * 2.)
+		.middle_of(panel);
+	// the fitness axis always includes zero, so the lines don't exaggerate small changes
+	let low: f32 = shown.iter().map(|stats| stats.mean).fold(0., f32::min);
+	let high: f32 = shown.iter().map(|stats| stats.best).fold(low, f32::max);
+	let range: f32 = (high - low).max(f32::EPSILON);
+	let point = |index: usize, fitness: f32| {
+		let x: f32 = match shown.len() {
+			1 => plot.x(),
+			len => plot.left() + plot.w() * index as f32 / (len - 1) as f32,
+		};
+		Vec2::new(x, plot.bottom() + plot.h() * (fitness - low) / range)
+	};
+	let mean: Vec<Vec2> = shown.iter().enumerate().map(|(index, stats)| point(index, stats.mean)).collect();
+	let best: Vec<Vec2> = shown.iter().enumerate().map(|(index, stats)| point(index, stats.best)).collect();
+	for (color, points) in [(mean_color, mean), (best_color, best)] {
+		match points.len() {
+			1 => {
+				draw.ellipse().xy(points[0]).radius(2.).color(color);
+			}
+			_ => {
+				draw.polyline().weight(1.5).points(points).color(color);
+			}
+		}
+	}
+	let last: &GenerationStats = shown.last().unwrap();
+	let label = Rect::from_w_h(plot.w() / 2., LABEL_HEIGHT).top_left_of(panel).shift_x(MARGIN).shift_y(-MARGIN);
+	let labels: [(String, Rect, Rgba); 3] = [
+		(format!("best {:.1}", last.best), label, best_color),
+		(format!("mean {:.1}", last.mean), label.shift_x(plot.w() / 2.), mean_color),
+		(
+			format!("generations {} - {}", shown[0].generation, last.generation),
+			Rect::from_w_h(plot.w(), LABEL_HEIGHT).bottom_left_of(panel).shift_x(MARGIN).shift_y(MARGIN),
+			Rgba::new(1.0, 1.0, 1.0, 0.7),
+		),
+	];
+	for (text, area, color) in labels {
+		draw.text(&text)
+			.xy(area.xy())
+			.wh(area.wh())
+			.font_size(11)
+			.left_justify()
+			.color(color);
+	}
+}
@@ -0,0 +1,87 @@
+//! Snapshots of a training run, so long runs survive being interrupted.
+//!
+//! A [`Checkpoint`] holds everything a [`Trainer`](crate::ga::Trainer) needs to carry on: the settings, the next
+//! population, the history so far and the exact state of the random number generator. A run
+//! resumed from a checkpoint makes the same decisions an uninterrupted run would have made.
+
+use std::path::Path;
+
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config::WorldConfig,
+	ga::{GaConfig, Population},
+	stats::GenerationStats,
+	storage::{self, StorageError},
+};
+
+/// Version of the checkpoint file format written by this build.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The state of a training run between two generations.
+/// Taken with [`Trainer::checkpoint`](crate::ga::Trainer::checkpoint) and resumed with
+/// [`Trainer::resume`](crate::ga::Trainer::resume).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+	/// Format version the file was written in.
+	pub version: u32,
+	pub config: GaConfig,
+	pub world: WorldConfig,
+	/// Seed the run was started from.
+	pub seed: u64,
+	/// The generation to evaluate next.
+	pub population: Population,
+	pub history: Vec<GenerationStats>,
+	/// The trainer's random number generator, mid-stream.
+	pub rng: ChaCha8Rng,
+}
+
+impl Checkpoint {
+	/// Reads a checkpoint from a file, refusing unknown format versions.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+		storage::read_versioned_json(path, FORMAT_VERSION)
+	}
+
+	/// Writes the checkpoint to a file.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StorageError> {
+		storage::write_json(path, self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ga::Trainer;
+
+	/// A trainer small enough to run a few generations quickly.
+	fn trainer() -> Trainer {
+		let config = GaConfig {population_size: 6, max_ticks: 120, ..GaConfig::default()};
+		let world = WorldConfig {enemy_count: 20, ..WorldConfig::default()};
+		Trainer::new(config, world, 11)
+	}
+
+	#[test]
+	fn resumed_run_matches_uninterrupted_run() {
+		let mut uninterrupted: Trainer = trainer();
+		for _ in 0..4 {
+			uninterrupted.run_generation();
+		}
+
+		let mut interrupted: Trainer = trainer();
+		for _ in 0..2 {
+			interrupted.run_generation();
+		}
+		let path = std::env::temp_dir().join(format!("checkpoint-test-{}.json", std::process::id()));
+		interrupted.checkpoint().save(&path).unwrap();
+		let checkpoint: Checkpoint = Checkpoint::load(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(checkpoint, interrupted.checkpoint());
+
+		let mut resumed: Trainer = Trainer::resume(checkpoint);
+		for _ in 0..2 {
+			resumed.run_generation();
+		}
+		assert_eq!(resumed.population, uninterrupted.population);
+	}
+}
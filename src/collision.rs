@@ -0,0 +1,217 @@
+//! Narrow phase collision tests between the shapes found in the world.
+//!
+//! Every test treats its first argument as a moving circle and reports how it overlaps the
+//! second shape as a [`Contact`]: which way to push the circle out and by how much. Shapes that
+//! only touch are not colliding.
+
+use nannou::geom::{Rect, Vec2};
+
+/// A circle, the shape of every entity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+	pub center: Vec2,
+	pub radius: f32,
+}
+
+/// A line segment between two points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+	pub start: Vec2,
+	pub end: Vec2,
+}
+
+/// How a circle overlaps another shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+	/// Unit vector pointing from the other shape towards the circle's center:
+	/// the direction to push the circle to separate the two.
+	pub normal: Vec2,
+	/// How far the shapes overlap along the normal.
+	pub penetration: f32,
+	/// The point on the other shape closest to the circle's center.
+	pub point: Vec2,
+}
+
+impl Segment {
+	/// The point on the segment closest to `point`.
+	pub fn closest_point(&self, point: Vec2) -> Vec2 {
+		let along: Vec2 = self.end - self.start;
+		let length_squared: f32 = along.length_squared();
+		if length_squared <= f32::EPSILON {
+			return self.start;
+		}
+		let t: f32 = ((point - self.start).dot(along) / length_squared).clamp(0., 1.);
+		self.start + along * t
+	}
+}
+
+/// Tests a circle against another circle.
+///
+/// Arguments
+/// * `a`: the circle to push out.
+/// * `b`: the circle it may overlap.
+///
+/// Returns
+/// * `contact`: `None` unless the circles overlap.
+pub fn circle_circle(a: &Circle, b: &Circle) -> Option<Contact> {
+	let offset: Vec2 = a.center - b.center;
+	let radii: f32 = a.radius + b.radius;
+	let distance_squared: f32 = offset.length_squared();
+	if distance_squared >= radii * radii {
+		return None;
+	}
+	let distance: f32 = distance_squared.sqrt();
+	// circles on top of each other can be pushed apart in any direction
+	let normal: Vec2 = match distance > f32::EPSILON {
+		true => offset / distance,
+		false => Vec2::X,
+	};
+	Some(Contact {normal, penetration: radii - distance, point: b.center + normal * b.radius})
+}
+
+/// Tests a circle against an axis-aligned rectangle.
+///
+/// Arguments
+/// * `circle`: the circle to push out.
+/// * `rect`: the rectangle it may overlap.
+///
+/// Returns
+/// * `contact`: `None` unless the circle overlaps the rectangle.
+pub fn circle_rect(circle: &Circle, rect: &Rect) -> Option<Contact> {
+	let center: Vec2 = circle.center;
+	let closest = Vec2::new(
+		center.x.clamp(rect.left(), rect.right()),
+		center.y.clamp(rect.bottom(), rect.top()),
+	);
+	if closest != center {
+		// the center is outside, so the contact is with the nearest point on the boundary
+		let offset: Vec2 = center - closest;
+		let distance: f32 = offset.length();
+		if distance >= circle.radius {
+			return None;
+		}
+		return Some(Contact {normal: offset / distance, penetration: circle.radius - distance, point: closest});
+	}
+	// the center is inside, so push it out through the nearest edge
+	let edges: [(f32, Vec2, Vec2); 4] = [
+		(center.x - rect.left(), -Vec2::X, Vec2::new(rect.left(), center.y)),
+		(rect.right() - center.x, Vec2::X, Vec2::new(rect.right(), center.y)),
+		(center.y - rect.bottom(), -Vec2::Y, Vec2::new(center.x, rect.bottom())),
+		(rect.top() - center.y, Vec2::Y, Vec2::new(center.x, rect.top())),
+	];
+	let (depth, normal, point) = edges
+		.into_iter()
+		.min_by(|a, b| a.0.total_cmp(&b.0))
+		.unwrap();
+	Some(Contact {normal, penetration: depth + circle.radius, point})
+}
+
+/// Tests a circle against a line segment.
+///
+/// Arguments
+/// * `circle`: the circle to push out.
+/// * `segment`: the segment it may overlap.
+///
+/// Returns
+/// * `contact`: `None` unless the circle overlaps the segment.
+pub fn circle_segment(circle: &Circle, segment: &Segment) -> Option<Contact> {
+	let point: Vec2 = segment.closest_point(circle.center);
+	let offset: Vec2 = circle.center - point;
+	let distance_squared: f32 = offset.length_squared();
+	if distance_squared >= circle.radius * circle.radius {
+		return None;
+	}
+	let distance: f32 = distance_squared.sqrt();
+	let normal: Vec2 = match distance > f32::EPSILON {
+		true => offset / distance,
+		// the center lies on the segment, so push it out sideways
+		false => (segment.end - segment.start).perp().try_normalize().unwrap_or(Vec2::X),
+	};
+	Some(Contact {normal, penetration: circle.radius - distance, point})
+}
+
+/// Tests a circle against a polygon.
+///
+/// Arguments
+/// * `circle`: the circle to push out.
+/// * `points`: the polygon's corners in order, either way around. The last one connects back to
+///   the first.
+///
+/// Returns
+/// * `contact`: `None` unless the circle overlaps the polygon's edges or lies inside it.
+pub fn circle_polygon(circle: &Circle, points: &[Vec2]) -> Option<Contact> {
+	let closest: Vec2 = polygon_edges(points)
+		.map(|edge| edge.closest_point(circle.center))
+		.min_by(|a, b| a.distance_squared(circle.center).total_cmp(&b.distance_squared(circle.center)))?;
+	if !polygon_contains(points, circle.center) {
+		return circle_circle(circle, &Circle {center: closest, radius: 0.});
+	}
+	// the center is inside, so push it out through the nearest edge
+	let offset: Vec2 = closest - circle.center;
+	let distance: f32 = offset.length();
+	let normal: Vec2 = match distance > f32::EPSILON {
+		true => offset / distance,
+		false => Vec2::X,
+	};
+	Some(Contact {normal, penetration: distance + circle.radius, point: closest})
+}
+
+/// The edges of a polygon, from every corner to the next.
+pub fn polygon_edges(points: &[Vec2]) -> impl Iterator<Item = Segment> + '_ {
+	points
+		.iter()
+		.zip(points.iter().cycle().skip(1))
+		.map(|(start, end)| Segment {start: *start, end: *end})
+}
+
+/// Whether a point lies inside a polygon, by counting the edges a horizontal ray from it crosses.
+fn polygon_contains(points: &[Vec2], point: Vec2) -> bool {
+	polygon_edges(points)
+		.filter(|edge| (edge.start.y > point.y) != (edge.end.y > point.y))
+		.filter(|edge| {
+			let t: f32 = (point.y - edge.start.y) / (edge.end.y - edge.start.y);
+			point.x < edge.start.x + t * (edge.end.x - edge.start.x)
+		})
+		.count() % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn diagonal_circles_with_overlapping_boxes_dont_touch() {
+		let a = Circle {center: Vec2::ZERO, radius: 1.};
+		let b = Circle {center: Vec2::new(1.5, 1.5), radius: 1.};
+		assert!(circle_circle(&a, &b).is_none());
+		let c = Circle {center: Vec2::new(1.2, 1.2), radius: 1.};
+		assert!(circle_circle(&a, &c).is_some());
+	}
+
+	#[test]
+	fn circle_inside_rect_is_pushed_out_through_nearest_edge() {
+		let rect = Rect::from_x_y_w_h(0., 0., 10., 4.);
+		let circle = Circle {center: Vec2::new(1., 0.5), radius: 1.};
+		let contact: Contact = circle_rect(&circle, &rect).unwrap();
+		assert_eq!(contact.normal, Vec2::Y);
+		assert_eq!(contact.penetration, 2.5);
+		assert_eq!(contact.point, Vec2::new(1., 2.));
+	}
+
+	#[test]
+	fn circle_inside_polygon_is_pushed_out_through_nearest_edge() {
+		let square: [Vec2; 4] = [
+			Vec2::new(-5., -5.),
+			Vec2::new(5., -5.),
+			Vec2::new(5., 5.),
+			Vec2::new(-5., 5.),
+		];
+		let circle = Circle {center: Vec2::new(3., 0.), radius: 1.};
+		let contact: Contact = circle_polygon(&circle, &square).unwrap();
+		assert_eq!(contact.normal, Vec2::X);
+		assert_eq!(contact.penetration, 3.);
+		assert_eq!(contact.point, Vec2::new(5., 0.));
+		let outside = Circle {center: Vec2::new(7., 0.), radius: 1.};
+		assert!(circle_polygon(&outside, &square).is_none());
+	}
+}
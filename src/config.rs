@@ -0,0 +1,477 @@
+//! Scenario settings that can be changed without recompiling.
+//!
+//! A [`Config`] is read from a TOML file with [`Config::load`]. Every setting has a default, so a
+//! file only needs to list what a scenario changes; an empty file describes the default world and
+//! training run.
+
+use std::{fmt, fs, io, path::Path};
+
+use nannou::{color::Rgb, geom::Rect};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	boundary::Boundary,
+	fitness::EpisodeStats,
+	ga::GaConfig,
+	obstacle::Obstacle,
+	physics::Motion,
+	vision::Vision,
+	world::{Enemy, Food, Player, Position},
+};
+
+/// Everything a scenario file can describe.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+	pub world: WorldConfig,
+	pub ga: GaConfig,
+}
+
+/// Largest seed a config can hold, since TOML integers are signed 64-bit.
+pub const MAX_SEED: u64 = i64::MAX as u64;
+
+/// Settings for setting up a world.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+	/// Seed for the run when none is given on the command line. Random if absent.
+	/// At most [`MAX_SEED`].
+	pub seed: Option<u64>,
+	/// Number of enemies scattered across the arena on every reset.
+	pub enemy_count: usize,
+	pub arena: ArenaConfig,
+	pub spawn: SpawnConfig,
+	pub player: PlayerConfig,
+	pub enemy: EnemyConfig,
+	pub food: FoodConfig,
+	/// Static walls and blocks placed in the arena.
+	pub obstacles: Vec<Obstacle>,
+	/// Where players start and must travel to, for a navigation task. Without one players start
+	/// in the middle of the arena and have nowhere to go.
+	pub goal: Option<GoalConfig>,
+}
+
+impl Default for WorldConfig {
+	fn default() -> Self {
+		Self {
+			seed: None,
+			enemy_count: 500,
+			arena: ArenaConfig::default(),
+			spawn: SpawnConfig::default(),
+			player: PlayerConfig::default(),
+			enemy: EnemyConfig::default(),
+			food: FoodConfig::default(),
+			obstacles: Vec::new(),
+			goal: None,
+		}
+	}
+}
+
+/// Size of the arena, which is centered on the origin.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArenaConfig {
+	pub width: f32,
+	pub height: f32,
+}
+
+impl Default for ArenaConfig {
+	fn default() -> Self {
+		Self {width: 512., height: 512.}
+	}
+}
+
+impl ArenaConfig {
+	/// The arena rectangle.
+	pub fn bounds(&self) -> Rect {
+		Rect::from_w_h(self.width, self.height)
+	}
+}
+
+/// Where enemies may appear when the world is set up.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpawnConfig {
+	/// Fraction of the arena enemies are scattered across, centered on the origin.
+	pub area: f32,
+	/// No enemy spawns closer than this to the player's spawn point along either axis.
+	pub clearance: f32,
+}
+
+impl Default for SpawnConfig {
+	fn default() -> Self {
+		Self {area: 0.80, clearance: 10.}
+	}
+}
+
+/// What every player looks like and how it moves.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerConfig {
+	pub radius: f32,
+	/// Red, green and blue in `0..=255`.
+	pub color: [u8; 3],
+	pub max_speed: f32,
+	pub max_force: f32,
+	pub mass: f32,
+	/// What happens to a player that reaches the edge of the arena. When players wrap around,
+	/// they also see and collide across the edges.
+	pub boundary: Boundary,
+	/// Energy a player starts with.
+	pub energy: f32,
+	/// Energy a player uses up per second. A player that runs out starves.
+	pub energy_drain: f32,
+}
+
+impl Default for PlayerConfig {
+	fn default() -> Self {
+		Self {
+			radius: 5.,
+			color: [255, 255, 255],
+			max_speed: 150.,
+			max_force: 800.,
+			mass: 1.,
+			boundary: Boundary::Clamp,
+			energy: 100.,
+			energy_drain: 0.,
+		}
+	}
+}
+
+impl PlayerConfig {
+	/// A living player at the origin without a brain.
+	pub fn player(&self) -> Player {
+		Player {
+			position: Position {x: 0., y: 0.},
+			radius: self.radius,
+			color: rgb(self.color),
+			alive: true,
+			motion: Motion::new(self.max_speed, self.max_force, self.mass),
+			heading: 0.,
+			energy: self.energy,
+			vision: Vision::default(),
+			sight: Vec::new(),
+			brain: None,
+			stats: EpisodeStats::default(),
+		}
+	}
+}
+
+/// What every enemy looks like and how it moves.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnemyConfig {
+	pub radius: f32,
+	/// Red, green and blue in `0..=255`.
+	pub color: [u8; 3],
+	pub max_speed: f32,
+	pub max_force: f32,
+	pub mass: f32,
+	/// What happens to an enemy that reaches the edge of the arena. Enemies killed by a lethal
+	/// wall are removed from the world.
+	pub boundary: Boundary,
+}
+
+impl Default for EnemyConfig {
+	fn default() -> Self {
+		Self {radius: 5., color: [255, 0, 0], max_speed: 15., max_force: 60., mass: 1., boundary: Boundary::Clamp}
+	}
+}
+
+impl EnemyConfig {
+	/// A living enemy at the origin.
+	pub fn enemy(&self) -> Enemy {
+		Enemy {
+			position: Position {x: 0., y: 0.},
+			radius: self.radius,
+			color: rgb(self.color),
+			alive: true,
+			motion: Motion::new(self.max_speed, self.max_force, self.mass),
+		}
+	}
+}
+
+/// Food items for players to collect.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoodConfig {
+	/// Number of items scattered across the arena on every reset.
+	pub count: usize,
+	pub radius: f32,
+	/// Red, green and blue in `0..=255`.
+	pub color: [u8; 3],
+	/// Energy a player gains from eating an item.
+	pub energy: f32,
+	/// When an eaten item comes back.
+	pub respawn: Respawn,
+}
+
+impl Default for FoodConfig {
+	/// No food, so surviving is the only thing to do.
+	fn default() -> Self {
+		Self {count: 0, radius: 4., color: [80, 220, 100], energy: 25., respawn: Respawn::Immediate}
+	}
+}
+
+impl FoodConfig {
+	/// An uneaten item at the origin.
+	pub fn food(&self) -> Food {
+		Food {position: Position {x: 0., y: 0.}, radius: self.radius, color: rgb(self.color), eaten_at: None}
+	}
+}
+
+/// When an eaten food item comes back, somewhere else in the arena.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Respawn {
+	/// Eaten items are gone for good.
+	Never,
+	/// A new item appears as soon as one is eaten.
+	#[default]
+	Immediate,
+	/// A new item appears a number of ticks after one is eaten.
+	Delayed {ticks: u64},
+}
+
+impl Respawn {
+	/// Number of ticks between an item being eaten and coming back, `None` if it never does.
+	pub fn delay(self) -> Option<u64> {
+		match self {
+			Respawn::Never => None,
+			Respawn::Immediate => Some(0),
+			Respawn::Delayed {ticks} => Some(ticks),
+		}
+	}
+}
+
+/// A start and a goal region across the arena from it. Players that reach the goal have
+/// succeeded and leave the world.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoalConfig {
+	/// Where players spawn.
+	pub start: [f32; 2],
+	/// Center of the goal region.
+	pub center: [f32; 2],
+	/// Radius of the goal region.
+	pub radius: f32,
+	/// No enemy spawns in the squares reaching this far around the start and beyond the goal
+	/// region, on top of the spawn clearance kept around the start.
+	pub clearance: f32,
+	/// Red, green and blue in `0..=255`.
+	pub color: [u8; 3],
+}
+
+impl Default for GoalConfig {
+	/// From the bottom left corner of the default arena to the top right one.
+	fn default() -> Self {
+		Self {start: [-200., -200.], center: [200., 200.], radius: 20., clearance: 40., color: [255, 215, 0]}
+	}
+}
+
+impl GoalConfig {
+	/// Where players spawn.
+	pub fn start(&self) -> Position {
+		Position {x: self.start[0], y: self.start[1]}
+	}
+
+	/// Center of the goal region.
+	pub fn center(&self) -> Position {
+		Position {x: self.center[0], y: self.center[1]}
+	}
+
+	/// The goal region's color.
+	pub fn rgb(&self) -> Rgb {
+		rgb(self.color)
+	}
+
+	/// Whether an enemy at the given position would start too close to the start or the goal.
+	pub fn excludes(&self, position: Position) -> bool {
+		let within = |center: [f32; 2], reach: f32| {
+			(position.x - center[0]).abs() < reach && (position.y - center[1]).abs() < reach
+		};
+		within(self.start, self.clearance) || within(self.center, self.radius + self.clearance)
+	}
+}
+
+/// Why a config file couldn't be loaded.
+#[derive(Debug)]
+pub enum ConfigError {
+	/// The file couldn't be read.
+	Io(io::Error),
+	/// The file isn't valid TOML or doesn't describe a config.
+	Parse(toml::de::Error),
+	/// The config couldn't be written as TOML.
+	Serialize(toml::ser::Error),
+	/// A setting is out of range; says which one and why.
+	Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ConfigError::Io(error) => write!(f, "couldn't read config: {}", error),
+			ConfigError::Parse(error) => write!(f, "invalid config: {}", error),
+			ConfigError::Serialize(error) => write!(f, "couldn't write config: {}", error),
+			ConfigError::Invalid(reason) => write!(f, "invalid config: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+	fn from(error: io::Error) -> Self {
+		ConfigError::Io(error)
+	}
+}
+
+impl From<toml::de::Error> for ConfigError {
+	fn from(error: toml::de::Error) -> Self {
+		ConfigError::Parse(error)
+	}
+}
+
+impl From<toml::ser::Error> for ConfigError {
+	fn from(error: toml::ser::Error) -> Self {
+		ConfigError::Serialize(error)
+	}
+}
+
+impl Config {
+	/// Reads a config from a TOML file. Settings missing from the file keep their defaults.
+	///
+	/// Arguments
+	/// * `path`: the file to read.
+	///
+	/// Returns
+	/// * `config`: the config, or why it couldn't be loaded.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+		let text: String = fs::read_to_string(path)?;
+		let config: Config = toml::from_str(&text)?;
+		config.validate()?;
+		Ok(config)
+	}
+
+	/// Checks the settings that would otherwise make a run panic or make no sense.
+	///
+	/// Returns
+	/// * `result`: nothing, or the first setting that is out of range.
+	pub fn validate(&self) -> Result<(), ConfigError> {
+		let invalid = |name: &str, reason: &str| Err(ConfigError::Invalid(format!("{} must be {}", name, reason)));
+		let Config {world, ga} = self;
+		let WorldConfig {arena, spawn, player, enemy, food, ..} = world;
+		if ga.population_size == 0 {
+			return invalid("ga.population_size", "at least 1");
+		}
+		let fractions: [(&str, f32); 2] = [("ga.mutation.rate", ga.mutation.rate), ("world.spawn.area", spawn.area)];
+		if let Some((name, _)) = fractions.iter().find(|(_, value)| !(0.0..=1.0).contains(value)) {
+			return invalid(name, "between 0 and 1");
+		}
+		// sizes and masses are divided by or sampled between, so zero breaks them as well
+		let positive: [(&str, f32); 11] = [
+			("world.arena.width", arena.width),
+			("world.arena.height", arena.height),
+			("world.player.radius", player.radius),
+			("world.player.max_speed", player.max_speed),
+			("world.player.max_force", player.max_force),
+			("world.player.mass", player.mass),
+			("world.enemy.radius", enemy.radius),
+			("world.enemy.max_speed", enemy.max_speed),
+			("world.enemy.max_force", enemy.max_force),
+			("world.enemy.mass", enemy.mass),
+			("world.food.radius", food.radius),
+		];
+		if let Some((name, _)) = positive.iter().find(|(_, value)| !(value.is_finite() && *value > 0.)) {
+			return invalid(name, "a finite number above 0");
+		}
+		let non_negative: [(&str, f32); 7] = [
+			("ga.mutation.strength", ga.mutation.strength),
+			("ga.vision.fov", ga.vision.fov),
+			("ga.vision.range", ga.vision.range),
+			("world.spawn.clearance", spawn.clearance),
+			("world.player.energy", player.energy),
+			("world.player.energy_drain", player.energy_drain),
+			("world.food.energy", food.energy),
+		];
+		if let Some((name, _)) = non_negative.iter().find(|(_, value)| !(value.is_finite() && *value >= 0.)) {
+			return invalid(name, "a finite number no less than 0");
+		}
+		Ok(())
+	}
+
+	/// A fingerprint of the settings, to tell whether two runs used the same scenario.
+	/// The seed is left out, since runs of one scenario usually differ only in their seed.
+	pub fn hash(&self) -> u64 {
+		let mut config: Config = self.clone();
+		config.world.seed = None;
+		// FNV-1a over the JSON form, which unlike `std::hash` is stable across builds
+		let bytes: Vec<u8> = serde_json::to_vec(&config).expect("configs are always serializable");
+		bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+			(hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+		})
+	}
+
+	/// Writes the config to a TOML file that [`Config::load`] reads back unchanged.
+	///
+	/// Arguments
+	/// * `path`: the file to write. It is replaced if it exists.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+		// going through a value orders every table after the plain settings, as TOML requires
+		let text: String = toml::to_string(&toml::Value::try_from(self)?)?;
+		fs::write(path, text)?;
+		Ok(())
+	}
+}
+
+/// Turns a color from a config file into a nannou color.
+fn rgb([red, green, blue]: [u8; 3]) -> Rgb {
+	Rgb::new(red as f32 / 255., green as f32 / 255., blue as f32 / 255.)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// The default config with one setting changed.
+	fn with(change: impl FnOnce(&mut Config)) -> Config {
+		let mut config = Config::default();
+		change(&mut config);
+		config
+	}
+
+	#[test]
+	fn default_config_is_valid() {
+		assert!(Config::default().validate().is_ok());
+	}
+
+	#[test]
+	fn out_of_range_settings_are_rejected() {
+		let configs: Vec<(&str, Config)> = vec![
+			("ga.population_size", with(|config| config.ga.population_size = 0)),
+			("ga.mutation.rate", with(|config| config.ga.mutation.rate = 1.5)),
+			("ga.mutation.strength", with(|config| config.ga.mutation.strength = -0.1)),
+			("ga.vision.fov", with(|config| config.ga.vision.fov = f32::INFINITY)),
+			("ga.vision.range", with(|config| config.ga.vision.range = -1.)),
+			("world.arena.width", with(|config| config.world.arena.width = 0.)),
+			("world.arena.height", with(|config| config.world.arena.height = f32::NAN)),
+			("world.spawn.area", with(|config| config.world.spawn.area = f32::NAN)),
+			("world.spawn.clearance", with(|config| config.world.spawn.clearance = -5.)),
+			("world.player.radius", with(|config| config.world.player.radius = 0.)),
+			("world.player.mass", with(|config| config.world.player.mass = 0.)),
+			("world.player.max_speed", with(|config| config.world.player.max_speed = f32::NAN)),
+			("world.player.energy", with(|config| config.world.player.energy = -1.)),
+			("world.player.energy_drain", with(|config| config.world.player.energy_drain = f32::NAN)),
+			("world.enemy.max_force", with(|config| config.world.enemy.max_force = -60.)),
+			("world.enemy.mass", with(|config| config.world.enemy.mass = f32::INFINITY)),
+			("world.food.radius", with(|config| config.world.food.radius = 0.)),
+			("world.food.energy", with(|config| config.world.food.energy = -25.)),
+		];
+		for (name, config) in configs {
+			match config.validate() {
+				Err(ConfigError::Invalid(reason)) => assert!(reason.starts_with(name), "{}: {}", name, reason),
+				result => panic!("{} wasn't rejected: {:?}", name, result),
+			}
+		}
+	}
+}
@@ -0,0 +1,165 @@
+//! How well a player did during an episode.
+//!
+//! While a player is alive the world records [`EpisodeStats`] about it. Once the episode is over,
+//! a [`Fitness`] function boils those statistics down to a single score for the genetic algorithm.
+//! The built-in [`FitnessFunction`]s each reward or penalize one behavior, and a
+//! [`WeightedFitness`] combines several of them.
+
+use std::collections::HashSet;
+
+use nannou::geom::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{boundary::Space, world::Enemy};
+
+/// Side length of the grid cells used to measure how much of the arena was explored.
+pub const EXPLORATION_CELL: f32 = 32.;
+
+/// Gap between a player and an enemy below which a pass counts as a near miss.
+pub const NEAR_MISS_GAP: f32 = 10.;
+
+/// Distance from the arena wall within which a player counts as hugging it.
+pub const WALL_MARGIN: f32 = 10.;
+
+/// What happened to a single player during an episode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EpisodeStats {
+	/// Number of ticks the player stayed alive.
+	pub ticks_survived: u64,
+	/// Total length of the player's path.
+	pub distance_travelled: f32,
+	/// Grid cells of [`EXPLORATION_CELL`] the player has been in.
+	pub visited: HashSet<(i32, i32)>,
+	/// Number of times an enemy came within [`NEAR_MISS_GAP`] without touching the player.
+	pub near_misses: u32,
+	/// Number of ticks spent within [`WALL_MARGIN`] of the arena wall.
+	pub wall_ticks: u64,
+	/// Number of food items eaten.
+	pub food_eaten: u32,
+	/// Tick on which the player reached the goal, `None` if it didn't or there is none.
+	pub reached_goal: Option<u64>,
+	/// How much closer to the goal than its start the player got at best.
+	pub goal_progress: f32,
+	/// Whether an enemy was within the near miss gap on the previous tick.
+	close_call: bool,
+}
+
+impl EpisodeStats {
+	/// Records one tick of a living player.
+	///
+	/// Arguments
+	/// * `previous`: where the player was at the start of the tick.
+	/// * `position`: where the player is at the end of the tick.
+	/// * `radius`: the player's radius.
+	/// * `enemies`: the enemies that may be within [`NEAR_MISS_GAP`] of the player.
+	/// * `space`: the arena. A wrapping arena has no wall to hug.
+	pub fn record<'a>(
+		&mut self,
+		previous: Vec2,
+		position: Vec2,
+		radius: f32,
+		mut enemies: impl Iterator<Item = &'a Enemy>,
+		space: &Space,
+	) {
+		self.ticks_survived += 1;
+		self.distance_travelled += space.distance(previous, position);
+		self.visited.insert((
+			(position.x / EXPLORATION_CELL).floor() as i32,
+			(position.y / EXPLORATION_CELL).floor() as i32,
+		));
+		// a near miss is counted once when an enemy gets close, not on every tick it stays close
+		let close_call: bool = enemies.any(|enemy| {
+			let gap: f32 = space.distance(position, enemy.position.into()) - radius - enemy.radius;
+			(0. ..NEAR_MISS_GAP).contains(&gap)
+		});
+		if close_call && !self.close_call {
+			self.near_misses += 1;
+		}
+		self.close_call = close_call;
+		let bounds = &space.bounds;
+		let wall: f32 = (position.x - bounds.left())
+			.min(bounds.right() - position.x)
+			.min(position.y - bounds.bottom())
+			.min(bounds.top() - position.y);
+		if !space.wrap && wall < WALL_MARGIN {
+			self.wall_ticks += 1;
+		}
+	}
+
+	/// Number of distinct grid cells the player has been in.
+	pub fn area_explored(&self) -> usize {
+		self.visited.len()
+	}
+}
+
+/// Scores a finished episode. Higher is better.
+pub trait Fitness {
+	fn evaluate(&self, stats: &EpisodeStats) -> f32;
+}
+
+/// The built-in fitness functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitnessFunction {
+	/// Ticks survived.
+	Survival,
+	/// Length of the path travelled.
+	Distance,
+	/// Number of grid cells visited.
+	Exploration,
+	/// Number of near misses.
+	NearMisses,
+	/// Minus the number of ticks spent hugging the wall.
+	WallPenalty,
+	/// Number of food items eaten.
+	Food,
+	/// `1` if the player reached the goal, `0` otherwise.
+	ReachedGoal,
+	/// How much closer to the goal than its start the player got.
+	GoalProgress,
+}
+
+impl Fitness for FitnessFunction {
+	fn evaluate(&self, stats: &EpisodeStats) -> f32 {
+		match self {
+			FitnessFunction::Survival => stats.ticks_survived as f32,
+			FitnessFunction::Distance => stats.distance_travelled,
+			FitnessFunction::Exploration => stats.area_explored() as f32,
+			FitnessFunction::NearMisses => stats.near_misses as f32,
+			FitnessFunction::WallPenalty => -(stats.wall_ticks as f32),
+			FitnessFunction::Food => stats.food_eaten as f32,
+			FitnessFunction::ReachedGoal => stats.reached_goal.is_some() as u8 as f32,
+			FitnessFunction::GoalProgress => stats.goal_progress,
+		}
+	}
+}
+
+/// One fitness function of a [`WeightedFitness`] and how much it counts.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FitnessTerm {
+	pub function: FitnessFunction,
+	pub weight: f32,
+}
+
+/// A weighted sum of fitness functions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WeightedFitness {
+	pub terms: Vec<FitnessTerm>,
+}
+
+impl Default for WeightedFitness {
+	/// Rewards survival alone.
+	fn default() -> Self {
+		Self {terms: vec![FitnessTerm {function: FitnessFunction::Survival, weight: 1.}]}
+	}
+}
+
+impl Fitness for WeightedFitness {
+	fn evaluate(&self, stats: &EpisodeStats) -> f32 {
+		self.terms
+			.iter()
+			.map(|term| term.function.evaluate(stats) * term.weight)
+			.sum()
+	}
+}
@@ -0,0 +1,591 @@
+//! The genetic algorithm that evolves Player brains.
+//!
+//! Every [`Genome`] is the flattened list of weights of a neural network. A generation is
+//! evaluated by letting each genome's brain steer a player through a [`World`] until it dies, and
+//! the run's [`Fitness`] function scores what happened during that episode. By default the whole
+//! generation shares one world, so it can be watched live; see [`Evaluation`]. The next
+//! generation is then bred from the current one: parents are picked by a [`SelectionMethod`],
+//! combined by a [`CrossoverMethod`] and tweaked by a [`MutationMethod`], while the best few
+//! genomes are carried over untouched.
+//!
+//! Isolated episodes are evaluated in parallel on rayon's thread pool. Every episode's world is
+//! seeded from the generation's seed alone, so the results don't depend on the number of threads
+//! or the order they finish in.
+
+use std::time::Instant;
+
+use nannou::rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, Normal};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	checkpoint::{self, Checkpoint},
+	config::WorldConfig,
+	fitness::{EpisodeStats, Fitness, WeightedFitness},
+	nn::{Activation, Network, Topology},
+	stats::GenerationStats,
+	vision::Vision,
+	world::{Input, Player, World, BRAIN_OUTPUTS},
+};
+
+/// Fixed time step used when simulating worlds without a window, in seconds.
+pub const DT: f32 = 1. / 60.;
+
+/// A candidate solution: the weights of one brain and how well it did.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+	pub genes: Vec<f32>,
+	pub fitness: f32,
+}
+
+impl Genome {
+	/// Creates an unevaluated genome from a list of genes.
+	pub fn new(genes: Vec<f32>) -> Self {
+		Self {genes, fitness: 0.}
+	}
+
+	/// Builds the brain this genome encodes.
+	///
+	/// Arguments
+	/// * `topology`: the shape of the network the genes were created for.
+	pub fn brain(&self, topology: &Topology) -> Network {
+		Network::from_weights(topology, self.genes.iter().copied())
+	}
+}
+
+/// All genomes of a single generation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Population {
+	pub genomes: Vec<Genome>,
+	/// Number of generations bred before this one.
+	pub generation: usize,
+}
+
+impl Population {
+	/// Creates the first generation from randomly initialized networks.
+	///
+	/// Arguments
+	/// * `rng`: random number generator to draw the weights from.
+	/// * `size`: number of genomes in the population.
+	/// * `topology`: the shape of every genome's brain.
+	pub fn random(rng: &mut impl Rng, size: usize, topology: &Topology) -> Self {
+		let genomes: Vec<Genome> = (0..size)
+			.map(|_| Genome::new(Network::random(rng, topology).weights().collect()))
+			.collect();
+		Self {genomes, generation: 0}
+	}
+
+	/// The genome with the highest fitness, if there are any genomes.
+	pub fn best(&self) -> Option<&Genome> {
+		self.genomes.iter().max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+	}
+
+	/// Average fitness of the population.
+	pub fn mean_fitness(&self) -> f32 {
+		match self.genomes.is_empty() {
+			true => 0.,
+			false => self.genomes.iter().map(|genome| genome.fitness).sum::<f32>() / self.genomes.len() as f32,
+		}
+	}
+}
+
+/// Picks a parent from the population.
+pub trait SelectionMethod {
+	/// Arguments
+	/// * `rng`: random number generator.
+	/// * `fitnesses`: the fitness of every genome in the population.
+	///
+	/// Returns
+	/// * `index`: the index of the selected genome.
+	fn select(&self, rng: &mut dyn RngCore, fitnesses: &[f32]) -> usize;
+}
+
+/// Combines two parents into a child.
+pub trait CrossoverMethod {
+	/// Arguments
+	/// * `rng`: random number generator.
+	/// * `a`, `b`: the genes of both parents, of equal length.
+	///
+	/// Returns
+	/// * `genes`: the genes of the child.
+	fn crossover(&self, rng: &mut dyn RngCore, a: &[f32], b: &[f32]) -> Vec<f32>;
+}
+
+/// Randomly alters a child's genes.
+pub trait MutationMethod {
+	/// Arguments
+	/// * `rng`: random number generator.
+	/// * `genes`: the genes to mutate in place.
+	fn mutate(&self, rng: &mut dyn RngCore, genes: &mut [f32]);
+}
+
+/// The built-in selection strategies.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Selection {
+	/// Picks genomes with a probability proportional to their fitness.
+	Roulette,
+	/// Picks the fittest of `size` genomes drawn at random.
+	Tournament {size: usize},
+	/// Picks genomes with a probability proportional to their rank, so a single outlier
+	/// can't take over the population.
+	Rank,
+}
+
+impl SelectionMethod for Selection {
+	fn select(&self, rng: &mut dyn RngCore, fitnesses: &[f32]) -> usize {
+		assert!(!fitnesses.is_empty(), "cannot select from an empty population");
+		match *self {
+			Selection::Roulette => {
+				// negative fitness gets no share of the wheel
+				let weights: Vec<f32> = fitnesses.iter().map(|fitness| fitness.max(0.)).collect();
+				spin_wheel(rng, &weights)
+			}
+			Selection::Tournament {size} => (0..size.max(1))
+				.map(|_| rng.gen_range(0..fitnesses.len()))
+				.max_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]))
+				.unwrap(),
+			Selection::Rank => {
+				let mut ranked: Vec<usize> = (0..fitnesses.len()).collect();
+				ranked.sort_by(|&a, &b| fitnesses[a].total_cmp(&fitnesses[b]));
+				// the worst genome gets a weight of 1, the best a weight of n
+				let weights: Vec<f32> = (1..=ranked.len()).map(|rank| rank as f32).collect();
+				ranked[spin_wheel(rng, &weights)]
+			}
+		}
+	}
+}
+
+/// Picks an index with a probability proportional to its weight.
+/// Falls back to a uniform pick when all weights are zero.
+fn spin_wheel(rng: &mut dyn RngCore, weights: &[f32]) -> usize {
+	let total: f32 = weights.iter().sum();
+	if total <= 0. {
+		return rng.gen_range(0..weights.len());
+	}
+	let mut spin: f32 = rng.gen_range(0.0..total);
+	for (index, weight) in weights.iter().enumerate() {
+		if spin < *weight {
+			return index;
+		}
+		spin -= weight;
+	}
+	// only reachable through floating point rounding
+	weights.iter().rposition(|weight| *weight > 0.).unwrap()
+}
+
+/// The built-in crossover operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Crossover {
+	/// Takes each gene from either parent with equal probability.
+	Uniform,
+	/// Takes the genes before a random cut from one parent and the rest from the other.
+	SinglePoint,
+	/// Blends each pair of genes with a random weight.
+	Arithmetic,
+}
+
+impl CrossoverMethod for Crossover {
+	fn crossover(&self, rng: &mut dyn RngCore, a: &[f32], b: &[f32]) -> Vec<f32> {
+		assert_eq!(a.len(), b.len(), "parents must have the same number of genes");
+		match self {
+			Crossover::Uniform => a.iter()
+				.zip(b)
+				.map(|(a, b)| if rng.gen_bool(0.5) { *a } else { *b })
+				.collect(),
+			Crossover::SinglePoint => {
+				let cut: usize = rng.gen_range(0..=a.len());
+				a[..cut].iter().chain(&b[cut..]).copied().collect()
+			}
+			Crossover::Arithmetic => a.iter()
+				.zip(b)
+				.map(|(a, b)| {
+					let blend: f32 = rng.gen_range(0.0..=1.0);
+					a * blend + b * (1. - blend)
+				})
+				.collect(),
+		}
+	}
+}
+
+/// Adds normally distributed noise to a fraction of the genes.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GaussianMutation {
+	/// Probability of each gene being mutated.
+	pub rate: f32,
+	/// Standard deviation of the noise added to a mutated gene.
+	pub strength: f32,
+}
+
+impl MutationMethod for GaussianMutation {
+	fn mutate(&self, rng: &mut dyn RngCore, genes: &mut [f32]) {
+		let noise = Normal::new(0., self.strength).expect("mutation strength must be finite");
+		for gene in genes.iter_mut() {
+			if rng.gen_bool(self.rate as f64) {
+				*gene += noise.sample(rng);
+			}
+		}
+	}
+}
+
+/// Breeds a new generation from an evaluated one.
+pub struct GeneticAlgorithm {
+	pub selection: Box<dyn SelectionMethod>,
+	pub crossover: Box<dyn CrossoverMethod>,
+	pub mutation: Box<dyn MutationMethod>,
+	/// Number of fittest genomes copied unchanged into the next generation.
+	pub elitism: usize,
+}
+
+impl GeneticAlgorithm {
+	/// Breeds the next generation.
+	///
+	/// Arguments
+	/// * `rng`: random number generator.
+	/// * `population`: the current, evaluated generation.
+	///
+	/// Returns
+	/// * `population`: the unevaluated next generation, of the same size.
+	pub fn evolve(&self, rng: &mut dyn RngCore, population: &Population) -> Population {
+		let Population {genomes, generation} = population;
+		let fitnesses: Vec<f32> = genomes.iter().map(|genome| genome.fitness).collect();
+		// the elite survive as they are
+		let mut ranked: Vec<&Genome> = genomes.iter().collect();
+		ranked.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+		let mut next: Vec<Genome> = ranked.iter()
+			.take(self.elitism.min(genomes.len()))
+			.map(|genome| Genome::new(genome.genes.clone()))
+			.collect();
+		// everyone else is bred from selected parents
+		while next.len() < genomes.len() {
+			let a: &Genome = &genomes[self.selection.select(rng, &fitnesses)];
+			let b: &Genome = &genomes[self.selection.select(rng, &fitnesses)];
+			let mut genes: Vec<f32> = self.crossover.crossover(rng, &a.genes, &b.genes);
+			self.mutation.mutate(rng, &mut genes);
+			next.push(Genome::new(genes));
+		}
+		Population {genomes: next, generation: generation + 1}
+	}
+}
+
+/// How the genomes of a generation are put through their episodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Evaluation {
+	/// Every genome gets a player in one shared world, dodging the same enemies at the same time.
+	Shared,
+	/// Every genome gets a world of its own, built from the same seed. The worlds are simulated
+	/// in parallel.
+	Isolated,
+}
+
+/// Settings for a training run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GaConfig {
+	/// Number of genomes in every generation.
+	pub population_size: usize,
+	/// What every player sees; decides the size of a brain's input layer.
+	pub vision: Vision,
+	/// Number of neurons in each hidden layer of a brain.
+	pub hidden_layers: Vec<usize>,
+	/// Activation used by every hidden layer of a brain.
+	pub hidden_activation: Activation,
+	/// Activation used by a brain's output layer. Outputs outside `[-1, 1]` are clamped when
+	/// steering, so bounded functions like tanh work best.
+	pub output_activation: Activation,
+	pub selection: Selection,
+	pub crossover: Crossover,
+	pub mutation: GaussianMutation,
+	/// Number of fittest genomes copied unchanged into the next generation.
+	pub elitism: usize,
+	/// An episode ends after this many ticks even if the player is still alive.
+	pub max_ticks: u64,
+	/// Scores every episode.
+	pub fitness: WeightedFitness,
+	pub evaluation: Evaluation,
+}
+
+impl Default for GaConfig {
+	fn default() -> Self {
+		Self {
+			population_size: 100,
+			vision: Vision::default(),
+			hidden_layers: vec![8],
+			hidden_activation: Activation::Tanh,
+			output_activation: Activation::Tanh,
+			selection: Selection::Tournament {size: 3},
+			crossover: Crossover::Uniform,
+			mutation: GaussianMutation {rate: 0.05, strength: 0.3},
+			elitism: 2,
+			max_ticks: 3600,
+			fitness: WeightedFitness::default(),
+			evaluation: Evaluation::Shared,
+		}
+	}
+}
+
+impl GaConfig {
+	/// The shape of every brain in the run.
+	pub fn topology(&self) -> Topology {
+		let layers: Vec<usize> = std::iter::once(self.vision.input_len())
+			.chain(self.hidden_layers.iter().copied())
+			.chain(std::iter::once(BRAIN_OUTPUTS))
+			.collect();
+		Topology {layers, hidden: self.hidden_activation, output: self.output_activation}
+	}
+
+	/// A player steered by the given brain, seeing what this config says it sees.
+	pub fn player(&self, brain: Network) -> Player {
+		Player {vision: self.vision, brain: Some(brain), ..Default::default()}
+	}
+
+	/// The genetic algorithm described by this config.
+	pub fn algorithm(&self) -> GeneticAlgorithm {
+		GeneticAlgorithm {
+			selection: Box::new(self.selection),
+			crossover: Box::new(self.crossover),
+			mutation: Box::new(self.mutation),
+			elitism: self.elitism,
+		}
+	}
+}
+
+/// Runs the generation loop: evaluate every genome, then breed the next generation.
+pub struct Trainer {
+	pub config: GaConfig,
+	/// The world every generation is evaluated in.
+	pub world: WorldConfig,
+	/// Seed the run was started from.
+	pub seed: u64,
+	/// The generation to evaluate next.
+	pub population: Population,
+	/// How every generation evaluated so far did, oldest first.
+	pub history: Vec<GenerationStats>,
+	algorithm: GeneticAlgorithm,
+	rng: ChaCha8Rng,
+	/// When evaluation of the current generation started.
+	started: Instant,
+}
+
+impl Trainer {
+	/// Creates a trainer with a random first generation.
+	///
+	/// Arguments
+	/// * `config`: settings for the run.
+	/// * `world`: the world every generation is evaluated in. Its seed is ignored.
+	/// * `seed`: seed for every random decision made during training, including the worlds.
+	pub fn new(config: GaConfig, world: WorldConfig, seed: u64) -> Self {
+		let mut rng = ChaCha8Rng::seed_from_u64(seed);
+		let population = Population::random(&mut rng, config.population_size, &config.topology());
+		let algorithm = config.algorithm();
+		Self {config, world, seed, population, history: Vec::new(), algorithm, rng, started: Instant::now()}
+	}
+
+	/// Picks a run back up exactly where a checkpoint left it.
+	pub fn resume(checkpoint: Checkpoint) -> Self {
+		let Checkpoint {config, world, seed, population, history, rng, ..} = checkpoint;
+		let algorithm = config.algorithm();
+		Self {config, world, seed, population, history, algorithm, rng, started: Instant::now()}
+	}
+
+	/// Everything needed to resume the run from here. Only take one between generations,
+	/// not while the world of [`Trainer::start_generation`] is being stepped.
+	pub fn checkpoint(&self) -> Checkpoint {
+		Checkpoint {
+			version: checkpoint::FORMAT_VERSION,
+			config: self.config.clone(),
+			world: self.world.clone(),
+			seed: self.seed,
+			population: self.population.clone(),
+			history: self.history.clone(),
+			rng: self.rng.clone(),
+		}
+	}
+
+	/// Evaluates the current generation and breeds the next one.
+	/// Every genome of a generation faces a world built from the same seed.
+	///
+	/// Returns
+	/// * `champion`: the fittest genome of the generation that was evaluated.
+	pub fn run_generation(&mut self) -> Genome {
+		match self.config.evaluation {
+			Evaluation::Shared => {
+				let mut world: World = self.start_generation();
+				run_episode(&mut world, self.config.max_ticks);
+				self.finish_generation(&world)
+			}
+			Evaluation::Isolated => {
+				self.started = Instant::now();
+				let topology: Topology = self.config.topology();
+				let world_seed: u64 = self.rng.gen();
+				let Trainer {config, world, population, ..} = self;
+				// collecting keeps population order however the episodes are scheduled
+				let episodes: Vec<EpisodeStats> = population.genomes
+					.par_iter_mut()
+					.map(|genome| {
+						let stats: EpisodeStats = evaluate(&genome.brain(&topology), config, world, world_seed);
+						genome.fitness = config.fitness.evaluate(&stats);
+						stats
+					})
+					.collect();
+				self.breed(&episodes)
+			}
+		}
+	}
+
+	/// Sets up the shared world for the current generation, with one player per genome in
+	/// population order. Step it until [`Trainer::is_finished`], then hand it back to
+	/// [`Trainer::finish_generation`].
+	pub fn start_generation(&mut self) -> World {
+		self.started = Instant::now();
+		let topology: Topology = self.config.topology();
+		let players: Vec<Player> = self.population.genomes
+			.iter()
+			.map(|genome| self.config.player(genome.brain(&topology)))
+			.collect();
+		World::with_players(self.world.clone(), self.rng.gen(), players)
+	}
+
+	/// Whether the shared world of a generation has run its course.
+	pub fn is_finished(&self, world: &World) -> bool {
+		world.is_over() || world.ticks >= self.config.max_ticks
+	}
+
+	/// Scores every genome from its player in the shared world and breeds the next generation.
+	///
+	/// Arguments
+	/// * `world`: the world created by [`Trainer::start_generation`].
+	///
+	/// Returns
+	/// * `champion`: the fittest genome of the generation that was evaluated.
+	pub fn finish_generation(&mut self, world: &World) -> Genome {
+		for (genome, player) in self.population.genomes.iter_mut().zip(&world.players) {
+			genome.fitness = self.config.fitness.evaluate(&player.stats);
+		}
+		let episodes: Vec<EpisodeStats> = world.players.iter().map(|player| player.stats.clone()).collect();
+		self.breed(&episodes)
+	}
+
+	/// Records how the evaluated population did and replaces it with the next generation.
+	///
+	/// Arguments
+	/// * `episodes`: what happened to every genome's player.
+	fn breed(&mut self, episodes: &[EpisodeStats]) -> Genome {
+		let champion: Genome = self.population.best().cloned().expect("population is empty");
+		let stats = GenerationStats::new(&self.population, episodes, self.started.elapsed());
+		self.history.push(stats);
+		self.population = self.algorithm.evolve(&mut self.rng, &self.population);
+		champion
+	}
+}
+
+/// Steps a world without input until every player has died or time runs out.
+///
+/// Arguments
+/// * `world`: the world to simulate.
+/// * `max_ticks`: the episode ends after this many ticks.
+pub fn run_episode(world: &mut World, max_ticks: u64) {
+	while !world.is_over() && world.ticks < max_ticks {
+		world.step(&Input::default(), DT);
+	}
+}
+
+/// Lets a brain steer a player through a fresh world until it dies or time runs out.
+///
+/// Arguments
+/// * `brain`: the network steering the player.
+/// * `config`: settings for the run.
+/// * `world`: the world to set up.
+/// * `seed`: seed of the world.
+///
+/// Returns
+/// * `stats`: what happened to the player, for the config's fitness function to score.
+pub fn evaluate(brain: &Network, config: &GaConfig, world: &WorldConfig, seed: u64) -> EpisodeStats {
+	let players: Vec<Player> = vec![config.player(brain.clone())];
+	let mut world = World::with_players(world.clone(), seed, players);
+	run_episode(&mut world, config.max_ticks);
+	world.players.swap_remove(0).stats
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Runs a few isolated generations on a pool of the given number of threads.
+	fn run(threads: usize) -> (Population, Vec<f32>) {
+		let config = GaConfig {
+			population_size: 8,
+			max_ticks: 120,
+			evaluation: Evaluation::Isolated,
+			..GaConfig::default()
+		};
+		let world = WorldConfig {enemy_count: 20, ..WorldConfig::default()};
+		let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+		pool.install(|| {
+			let mut trainer = Trainer::new(config, world, 3);
+			let fitness: Vec<f32> = (0..3).map(|_| trainer.run_generation().fitness).collect();
+			(trainer.population, fitness)
+		})
+	}
+
+	#[test]
+	fn isolated_evaluation_is_independent_of_thread_count() {
+		assert_eq!(run(1), run(8));
+	}
+
+	/// How often a selection method picks each genome over many draws.
+	fn picks(selection: Selection, fitnesses: &[f32]) -> Vec<usize> {
+		let mut rng = ChaCha8Rng::seed_from_u64(1);
+		let mut picks: Vec<usize> = vec![0; fitnesses.len()];
+		for _ in 0..4000 {
+			picks[selection.select(&mut rng, fitnesses)] += 1;
+		}
+		picks
+	}
+
+	#[test]
+	fn roulette_never_picks_genomes_without_weight() {
+		assert_eq!(picks(Selection::Roulette, &[0., 5., -3., 0.]), vec![0, 4000, 0, 0]);
+		let mut rng = ChaCha8Rng::seed_from_u64(2);
+		assert!((0..1000).all(|_| spin_wheel(&mut rng, &[0., 0., 1e-3, 0.]) == 2));
+	}
+
+	#[test]
+	fn rank_and_tournament_prefer_fitter_genomes() {
+		let fitnesses: [f32; 4] = [3., -1., 10., 7.];
+		for selection in [Selection::Rank, Selection::Tournament {size: 3}] {
+			let picks: Vec<usize> = picks(selection, &fitnesses);
+			// from least to most fit
+			let ordered: [usize; 4] = [picks[1], picks[0], picks[3], picks[2]];
+			assert!(ordered.windows(2).all(|pair| pair[0] < pair[1]), "{:?}: {:?}", selection, picks);
+		}
+	}
+
+	#[test]
+	fn crossover_only_copies_genes_from_the_parents() {
+		let a: Vec<f32> = (0..20).map(|gene| gene as f32).collect();
+		let b: Vec<f32> = (0..20).map(|gene| gene as f32 + 100.).collect();
+		let mut rng = ChaCha8Rng::seed_from_u64(3);
+		for _ in 0..100 {
+			let uniform: Vec<f32> = Crossover::Uniform.crossover(&mut rng, &a, &b);
+			assert!(uniform.iter().enumerate().all(|(index, gene)| *gene == a[index] || *gene == b[index]));
+			// a single cut means a prefix of one parent followed by the rest of the other
+			let single_point: Vec<f32> = Crossover::SinglePoint.crossover(&mut rng, &a, &b);
+			let cut: usize = single_point.iter().zip(&a).take_while(|(child, a)| child == a).count();
+			assert_eq!(single_point[cut..], b[cut..]);
+		}
+	}
+
+	#[test]
+	fn mutation_without_rate_leaves_genes_unchanged() {
+		let genes: Vec<f32> = (0..50).map(|gene| gene as f32 * 0.1).collect();
+		let mut mutated: Vec<f32> = genes.clone();
+		let mut rng = ChaCha8Rng::seed_from_u64(4);
+		GaussianMutation {rate: 0., strength: 1.}.mutate(&mut rng, &mut mutated);
+		assert_eq!(mutated, genes);
+	}
+}
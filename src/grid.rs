@@ -0,0 +1,194 @@
+//! Broad phase for proximity queries.
+//!
+//! Checking every player against every enemy doesn't scale to large populations or crowded
+//! arenas. A [`SpatialGrid`] buckets enemies into uniform cells once per step, so collisions and
+//! sensors only have to look at the few enemies in the cells around them. On a torus, queries
+//! near an edge also look in the cells across it.
+
+use std::{collections::HashMap, ops::Range, vec};
+
+use nannou::geom::Vec2;
+
+use crate::boundary::Space;
+
+/// Default side length of a grid cell.
+pub const CELL_SIZE: f32 = 32.;
+
+/// Uniform grid of cells, each listing the indices of the items whose center lies inside it.
+#[derive(Clone, Debug)]
+pub struct SpatialGrid {
+	cell_size: f32,
+	cells: HashMap<(i32, i32), Vec<usize>>,
+	/// Largest radius of any item, so queries can reach items that overlap from a neighbor cell.
+	max_radius: f32,
+}
+
+impl SpatialGrid {
+	/// Creates an empty grid.
+	///
+	/// Arguments
+	/// * `cell_size`: side length of each cell. Around twice the typical item radius works well.
+	pub fn new(cell_size: f32) -> Self {
+		Self {cell_size, cells: HashMap::new(), max_radius: 0.}
+	}
+
+	/// Replaces the grid's contents. Cells keep their allocations between rebuilds.
+	///
+	/// Arguments
+	/// * `items`: the center and radius of every item, in index order.
+	pub fn rebuild(&mut self, items: impl IntoIterator<Item = (Vec2, f32)>) {
+		for cell in self.cells.values_mut() {
+			cell.clear();
+		}
+		self.max_radius = 0.;
+		for (index, (center, radius)) in items.into_iter().enumerate() {
+			self.max_radius = self.max_radius.max(radius);
+			let cell = self.cell(center);
+			self.cells.entry(cell).or_default().push(index);
+		}
+	}
+
+	/// Indices of the items that may lie within `radius` of `center`.
+	/// Every item that does is included, along with some that don't.
+	pub fn query(&self, center: Vec2, radius: f32) -> Vec<usize> {
+		let reach: Vec2 = Vec2::splat(radius + self.max_radius);
+		let (min, max) = (self.cell(center - reach), self.cell(center + reach));
+		let mut found: Vec<usize> = Vec::new();
+		for x in min.0..=max.0 {
+			for y in min.1..=max.1 {
+				if let Some(cell) = self.cells.get(&(x, y)) {
+					found.extend_from_slice(cell);
+				}
+			}
+		}
+		found
+	}
+
+	/// The cell a point falls into.
+	fn cell(&self, point: Vec2) -> (i32, i32) {
+		((point.x / self.cell_size).floor() as i32, (point.y / self.cell_size).floor() as i32)
+	}
+}
+
+/// How the world finds the enemies near a point.
+#[derive(Clone, Debug)]
+pub enum BroadPhase {
+	/// Considers every enemy for every query.
+	BruteForce,
+	/// Considers only the enemies in nearby grid cells.
+	Grid(SpatialGrid),
+}
+
+impl Default for BroadPhase {
+	fn default() -> Self {
+		BroadPhase::Grid(SpatialGrid::new(CELL_SIZE))
+	}
+}
+
+impl BroadPhase {
+	/// Brings the broad phase up to date with where the items are now.
+	///
+	/// Arguments
+	/// * `items`: the center and radius of every item, in index order.
+	pub fn rebuild(&mut self, items: impl IntoIterator<Item = (Vec2, f32)>) {
+		if let BroadPhase::Grid(grid) = self {
+			grid.rebuild(items);
+		}
+	}
+
+	/// Indices of the items that may lie within `radius` of `center`.
+	///
+	/// Arguments
+	/// * `center`, `radius`: the area of interest.
+	/// * `len`: the total number of items.
+	pub fn near(&self, center: Vec2, radius: f32, len: usize) -> Near {
+		match self {
+			BroadPhase::BruteForce => Near::All(0..len),
+			BroadPhase::Grid(grid) => Near::Found(grid.query(center, radius).into_iter()),
+		}
+	}
+
+	/// Indices of the items that may lie within `radius` of `center`, measured the short way
+	/// around when the space wraps. Every index is returned once.
+	///
+	/// Arguments
+	/// * `space`: the arena the items live in.
+	/// * `center`, `radius`: the area of interest.
+	/// * `len`: the total number of items.
+	pub fn near_in(&self, space: &Space, center: Vec2, radius: f32, len: usize) -> Near {
+		match (self, space.wrap) {
+			(BroadPhase::Grid(grid), true) => {
+				let mut found: Vec<usize> = space
+					.images(center, radius + grid.max_radius)
+					.into_iter()
+					.flat_map(|image| grid.query(image, radius))
+					.collect();
+				found.sort_unstable();
+				found.dedup();
+				Near::Found(found.into_iter())
+			}
+			_ => self.near(center, radius, len),
+		}
+	}
+}
+
+/// Iterator over the indices returned by [`BroadPhase::near`].
+#[derive(Clone, Debug)]
+pub enum Near {
+	All(Range<usize>),
+	Found(vec::IntoIter<usize>),
+}
+
+impl Iterator for Near {
+	type Item = usize;
+
+	fn next(&mut self) -> Option<usize> {
+		match self {
+			Near::All(range) => range.next(),
+			Near::Found(found) => found.next(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use nannou::{
+		geom::Rect,
+		rand::{Rng, SeedableRng},
+	};
+	use rand_chacha::ChaCha8Rng;
+
+	use super::*;
+
+	/// Checks that the grid finds every item a brute-force search finds, near the edges too.
+	fn assert_grid_finds_every_hit(wrap: bool) {
+		let space = Space {bounds: Rect::from_w_h(512., 512.), wrap};
+		let mut rng = ChaCha8Rng::seed_from_u64(5);
+		let mut point = || Vec2::new(rng.gen_range(-256.0..256.0), rng.gen_range(-256.0..256.0));
+		let items: Vec<(Vec2, f32)> = (0..300).map(|index| (point(), 2. + (index % 5) as f32)).collect();
+		let queries: Vec<Vec2> = (0..200).map(|_| point()).collect();
+		let mut grid = BroadPhase::default();
+		grid.rebuild(items.iter().copied());
+		for center in queries {
+			let radius: f32 = 20.;
+			let found: Vec<usize> = grid.near_in(&space, center, radius, items.len()).collect();
+			let hits = (0..items.len()).filter(|index| {
+				let (item, item_radius) = items[*index];
+				space.distance(center, item) < radius + item_radius
+			});
+			for hit in hits {
+				assert!(found.contains(&hit), "grid missed item {} near {:?}", hit, center);
+			}
+		}
+	}
+
+	#[test]
+	fn grid_finds_every_brute_force_hit() {
+		assert_grid_finds_every_hit(false);
+	}
+
+	#[test]
+	fn grid_finds_every_brute_force_hit_across_wrapped_edges() {
+		assert_grid_finds_every_hit(true);
+	}
+}
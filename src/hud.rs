@@ -0,0 +1,90 @@
+//! A text overlay with the numbers worth glancing at while a world is running.
+//!
+//! The front-end gathers whatever it knows into a [`HudStats`] every frame and [`draw_hud`] prints
+//! it in the top left corner of the window. Values a mode doesn't have, like the generation
+//! outside of training, are left out.
+
+use nannou::{color::Rgba, geom::Rect, Draw};
+
+use crate::{
+	panel::{draw_panel, Corner, MARGIN},
+	playback::Playback,
+};
+
+/// Width of the HUD panel.
+const PANEL_WIDTH: f32 = 200.;
+
+/// Height of a line of text.
+const LINE_HEIGHT: f32 = 16.;
+
+/// What the HUD shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HudStats {
+	/// Number of generations bred so far, when training.
+	pub generation: Option<usize>,
+	/// Number of players still alive.
+	pub alive: usize,
+	/// Number of players in the world.
+	pub players: usize,
+	/// Number of players that reached the goal, in scenarios with one.
+	pub reached_goal: Option<usize>,
+	/// Number of steps the world has been simulated for.
+	pub ticks: u64,
+	/// Highest fitness of any genome so far, when training.
+	pub best_fitness: Option<f32>,
+	/// Frames drawn per second.
+	pub fps: f32,
+	/// How fast the world is being simulated.
+	pub playback: Playback,
+	/// Seed of the run.
+	pub seed: u64,
+}
+
+impl HudStats {
+	/// The HUD's text, one entry per line.
+	pub fn lines(&self) -> Vec<String> {
+		let mut lines: Vec<String> = Vec::new();
+		if let Some(generation) = self.generation {
+			lines.push(format!("generation: {}", generation));
+		}
+		lines.push(format!("alive: {} / {}", self.alive, self.players));
+		if let Some(reached) = self.reached_goal {
+			lines.push(format!("reached goal: {}", reached));
+		}
+		lines.push(format!("ticks: {}", self.ticks));
+		if let Some(best) = self.best_fitness {
+			lines.push(format!("best fitness: {:.1}", best));
+		}
+		lines.push(format!("fps: {:.0}", self.fps));
+		lines.push(match self.playback.paused {
+			true => format!("speed: {} (paused)", self.playback.speed.label()),
+			false => format!("speed: {}", self.playback.speed.label()),
+		});
+		lines.push(format!("seed: {}", self.seed));
+		lines
+	}
+}
+
+/// Draws the HUD on a translucent panel in the top left corner of the window.
+///
+/// Arguments
+/// * `draw`: nannou::draw instance.
+/// * `window`: the window's rectangle.
+/// * `stats`: what to show.
+pub fn draw_hud(draw: &Draw, window: &Rect, stats: &HudStats) {
+	let lines: Vec<String> = stats.lines();
+	let height: f32 = lines.len() as f32 * LINE_HEIGHT + MARGIN * 2.;
+	let panel: Rect = draw_panel(draw, window, Corner::TopLeft, PANEL_WIDTH, height);
+	for (index, line) in lines.iter().enumerate() {
+		let row = Rect::from_w_h(PANEL_WIDTH - MARGIN * 2., LINE_HEIGHT)
+			.top_left_of(panel)
+			.shift_x(MARGIN)
+			.shift_y(-MARGIN - index as f32 * LINE_HEIGHT);
+		draw.text(line)
+			.xy(row.xy())
+			.wh(row.wh())
+			.font_size(12)
+			.left_justify()
+			.color(Rgba::new(1.0, 1.0, 1.0, 0.9));
+	}
+}
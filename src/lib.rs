@@ -0,0 +1,29 @@
+//! The simulation behind the creative coding exercise: a headless environment created to test the
+//! capabilities of a genetic algorithm.
+//!
+//! The goal is to have a player entity navigate through the environment avoiding collision with
+//! opposing entities, which will kill the player. Nothing in here opens a window, so worlds can be
+//! stepped as fast as the CPU allows; the `creative-coding` binary is just one front-end to it.
+
+pub mod boundary;
+pub mod camera;
+pub mod champion;
+pub mod chart;
+pub mod checkpoint;
+pub mod collision;
+pub mod config;
+pub mod fitness;
+pub mod ga;
+pub mod grid;
+pub mod hud;
+pub mod network_view;
+pub mod nn;
+pub mod obstacle;
+pub mod panel;
+pub mod physics;
+pub mod playback;
+pub mod recording;
+pub mod stats;
+pub mod storage;
+pub mod vision;
+pub mod world;
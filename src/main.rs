@@ -1,46 +1,627 @@
 //! This program is a creative coding exercise used as a visual representation
 //! of an environment created to test the capabilities of a genetic algorithm.
-/// 
-/// The goal is to have a player entity navigate through the environment avoiding collision with
-/// opposing entities, which will kill the player.
+//!
+//! The goal is to have a player entity navigate through the environment avoiding collision with
+//! opposing entities, which will kill the player.
+//!
+//! The program has a subcommand per way of running the simulation: `play` to steer the player
+//! with the mouse (the default), `train` to evolve brains, `watch` to see a saved champion at
+//! work and `replay` to play back a recorded session. Run with `--help` for their options.
+//!
+//! In every window, Space pauses, Period steps once, 1 to 4 pick the simulation speed and R
+//! restarts. Dragging with the right mouse button pans, scrolling zooms and C frames the arena
+//! again.
 
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::{Args, Parser, Subcommand};
+use creative_coding::{
+    camera::Camera,
+    champion::Champion,
+    chart,
+    checkpoint::Checkpoint,
+    config::{self, Config},
+    ga::{self, Genome, Trainer},
+    hud::{self, HudStats},
+    network_view,
+    playback::{Playback, Speed},
+    recording::{self, Recording},
+    stats::StatsLog,
+    world::{self, Input, Position, ViewOptions, World},
+};
 use nannou::prelude::*;
-use self::world::World;
 
-mod world;
+/// Longest time a single frame may advance the world by, in seconds. A stalled frame would
+/// otherwise move entities far enough in one step to pass straight through enemies and walls.
+const MAX_FRAME_DT: f32 = ga::DT * 2.;
+
+/// Largest width or height the window opens with, in pixels. Bigger arenas are scaled down to
+/// fit by the camera.
+const MAX_WINDOW_SIZE: f32 = 1024.;
+
+/// Evolves players that dodge enemies, or lets you play one yourself.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Steer the player with the mouse. Left click restarts once the player has died.
+    Play {
+        #[command(flatten)]
+        scenario: Scenario,
+        /// Save the session to this file when the window closes, to replay it later.
+        #[arg(long)]
+        record: Option<PathBuf>,
+    },
+    /// Evolve brains, headless unless `--live` is given.
+    Train(TrainArgs),
+    /// Watch a saved champion dodge enemies. Left click restarts once it has died.
+    Watch {
+        /// The champion's file, as saved by `train --output`.
+        champion: PathBuf,
+        #[command(flatten)]
+        scenario: Scenario,
+    },
+    /// Play back a session recorded with `play --record`.
+    Replay {
+        recording: PathBuf,
+    },
+}
+
+/// Options of the `train` subcommand.
+#[derive(Args)]
+struct TrainArgs {
+    #[command(flatten)]
+    scenario: Scenario,
+    /// Evaluate generations until this many have been evaluated, counting those before a resume.
+    #[arg(long, default_value_t = 100)]
+    generations: usize,
+    /// Number of genomes in every generation; overrides the config's.
+    #[arg(long)]
+    population: Option<usize>,
+    /// Directory to save the config, the best champion so far and checkpoints to.
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Save a checkpoint to the output directory after every this many generations.
+    #[arg(long, default_value_t = 10)]
+    checkpoint_every: usize,
+    /// Continue the run saved in this checkpoint instead of starting a new one.
+    #[arg(long, conflicts_with_all = ["config", "seed", "enemies", "width", "height", "population"])]
+    resume: Option<PathBuf>,
+    /// Watch every generation evolve in the window, until it is closed.
+    /// Only works with shared evaluation, where the whole generation is in one world.
+    #[arg(long)]
+    live: bool,
+    /// Number of threads evaluating isolated episodes. Defaults to one per core; the results are
+    /// the same either way.
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+impl TrainArgs {
+    /// Starts the run, or picks it back up from its checkpoint.
+    /// Exits the program if the config or checkpoint can't be loaded.
+    fn trainer(&self) -> Trainer {
+        if let Some(path) = &self.resume {
+            let checkpoint = Checkpoint::load(path).unwrap_or_else(|error| exit_with(error));
+            return Trainer::resume(checkpoint);
+        }
+        let (mut config, seed) = self.scenario.load();
+        config.ga.population_size = self.population.unwrap_or(config.ga.population_size);
+        config.validate().unwrap_or_else(|error| exit_with(error));
+        Trainer::new(config.ga, config.world, seed)
+    }
+
+    /// Where to save the run, if anywhere.
+    fn output(&self, trainer: &Trainer) -> Option<RunOutput> {
+        self.output
+            .clone()
+            .map(|directory| RunOutput::create(directory, trainer, self.checkpoint_every))
+    }
+}
+
+/// The scenario to run and overrides for its settings.
+#[derive(Args, Clone, Debug, Default)]
+struct Scenario {
+    /// TOML file describing the world and the training run. Defaults are used without one.
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Seed for the run; overrides the config's. Random if neither gives one.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(..=config::MAX_SEED))]
+    seed: Option<u64>,
+    /// Number of enemies; overrides the config's.
+    #[arg(long)]
+    enemies: Option<usize>,
+    /// Arena width; overrides the config's.
+    #[arg(long)]
+    width: Option<f32>,
+    /// Arena height; overrides the config's.
+    #[arg(long)]
+    height: Option<f32>,
+}
+
+impl Scenario {
+    /// Loads the config with the overrides applied, and picks the seed of the run.
+    /// The seed is written back into the config, so saving it makes the run reproducible.
+    /// Exits the program if the config can't be loaded or an override is out of range.
+    fn load(&self) -> (Config, u64) {
+        let mut config: Config = match &self.config {
+            Some(path) => Config::load(path).unwrap_or_else(|error| exit_with(error)),
+            None => Config::default(),
+        };
+        let world = &mut config.world;
+        world.enemy_count = self.enemies.unwrap_or(world.enemy_count);
+        world.arena.width = self.width.unwrap_or(world.arena.width);
+        world.arena.height = self.height.unwrap_or(world.arena.height);
+        // seeds are saved with the config, so they have to fit in a TOML integer
+        let seed: u64 = self.seed.or(world.seed).unwrap_or_else(|| random_range(0, config::MAX_SEED));
+        world.seed = Some(seed);
+        config.validate().unwrap_or_else(|error| exit_with(error));
+        (config, seed)
+    }
+}
+
+/// What the window is doing.
+enum Mode {
+    /// The player follows the mouse, and the session is recorded if asked to.
+    Play {recording: Option<(PathBuf, Recording)>},
+    /// The world holds one player per genome of the current generation.
+    Train {trainer: Box<Trainer>, output: Option<RunOutput>},
+    /// The world holds a single player steered by a saved brain.
+    Watch,
+    /// The world is stepped through the frames of a recording; `frame` is the next one.
+    Replay {recording: Recording, frame: usize},
+}
 
 /// Defines the app's state in nannou.
-struct Model {world: World}
+/// `selected` is the player whose brain is drawn and `pointer` is where the mouse was last seen,
+/// in window coordinates.
+struct Model {
+    world: World,
+    mode: Mode,
+    view_options: ViewOptions,
+    selected: Option<usize>,
+    playback: Playback,
+    camera: Camera,
+    pointer: Vec2,
+}
 
-/// Main entry point. Builds the app, passes a function to retrieve state
-/// and passes a function to call after every update.
+/// Main entry point. Trains headless when asked to, otherwise builds the app, passes a function
+/// to retrieve state and passes a function to call after every update.
 fn main() {
+    if let Some(Command::Train(args)) = Cli::parse().command {
+        if let Some(threads) = args.threads {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global()
+                .unwrap_or_else(|error| exit_with(error));
+        }
+        if !args.live {
+            train(&args);
+            return;
+        }
+    }
     nannou::app(model)
         .update(update)
+        .exit(exit)
         .run();
 }
 
+/// Evolves brains without opening a window, reporting the champion of every generation.
+fn train(args: &TrainArgs) {
+    let mut trainer = args.trainer();
+    let mut output: Option<RunOutput> = args.output(&trainer);
+    println!(
+        "training up to generation {} with seed {}, from generation {}",
+        args.generations,
+        trainer.seed,
+        trainer.population.generation,
+    );
+    while trainer.population.generation < args.generations {
+        let champion = trainer.run_generation();
+        println!("generation {}: best fitness {}", trainer.population.generation - 1, champion.fitness);
+        if let Some(output) = output.as_mut() {
+            output.generation_done(&trainer, champion);
+        }
+    }
+}
+
+/// Where a training run is saved: the config it ran with, its best champion so far, the
+/// statistics of every generation and checkpoints to resume it from.
+struct RunOutput {
+    directory: PathBuf,
+    config: Config,
+    seed: u64,
+    best: Option<f32>,
+    stats: StatsLog,
+    checkpoint_every: usize,
+}
+
+impl RunOutput {
+    /// Creates the directory, saves the config to it and starts the statistics logs.
+    /// Exits the program if that isn't possible.
+    ///
+    /// Arguments
+    /// * `directory`: where to save the run.
+    /// * `trainer`: the run, fresh or resumed.
+    /// * `checkpoint_every`: number of generations between checkpoints.
+    fn create(directory: PathBuf, trainer: &Trainer, checkpoint_every: usize) -> Self {
+        let mut config = Config {world: trainer.world.clone(), ga: trainer.config.clone()};
+        config.world.seed = Some(trainer.seed);
+        std::fs::create_dir_all(&directory).unwrap_or_else(|error| exit_with(error));
+        config.save(directory.join("config.toml")).unwrap_or_else(|error| exit_with(error));
+        // a resumed run only replaces the saved champion with a better one
+        let best: Option<f32> = trainer.history.iter().map(|stats| stats.best).reduce(f32::max);
+        let stats = StatsLog::create(&directory, &trainer.history).unwrap_or_else(|error| exit_with(error));
+        Self {directory, config, seed: trainer.seed, best, stats, checkpoint_every}
+    }
+
+    /// Logs the statistics of the generation just evaluated, saves its champion if it beats every
+    /// champion before it, and a checkpoint when one is due.
+    ///
+    /// Arguments
+    /// * `trainer`: the run, between two generations.
+    /// * `champion`: the fittest genome of the generation.
+    fn generation_done(&mut self, trainer: &Trainer, champion: Genome) {
+        if let Some(stats) = trainer.history.last() {
+            if let Err(error) = self.stats.append(stats) {
+                eprintln!("couldn't log statistics: {}", error);
+            }
+        }
+        if self.best.is_none_or(|best| champion.fitness > best) {
+            self.best = Some(champion.fitness);
+            let generation: usize = trainer.population.generation - 1;
+            let champion = Champion::new(champion, &self.config, generation, self.seed);
+            if let Err(error) = champion.save(self.directory.join("champion.json")) {
+                eprintln!("couldn't save champion: {}", error);
+            }
+        }
+        if self.checkpoint_every > 0 && trainer.population.generation.is_multiple_of(self.checkpoint_every) {
+            if let Err(error) = trainer.checkpoint().save(self.directory.join("checkpoint.json")) {
+                eprintln!("couldn't save checkpoint: {}", error);
+            }
+        }
+    }
+}
+
+/// Prints an error and exits the program.
+fn exit_with(error: impl std::fmt::Display) -> ! {
+    eprintln!("{}", error);
+    process::exit(1);
+}
+
 /// Draws a window and passes state to the app.
+/// What the window shows depends on the subcommand.
 fn model(app: &App) -> Model {
+    let command = Cli::parse().command.unwrap_or(Command::Play {scenario: Scenario::default(), record: None});
+    let (world, mode, title) = match command {
+        Command::Play {scenario, record} => {
+            let (config, seed) = scenario.load();
+            let world = World::new(config.world, seed);
+            let recording = record.map(|path| (path, Recording::new(&world)));
+            (world, Mode::Play {recording}, format!("Environment (seed {})", seed))
+        }
+        Command::Train(args) => {
+            let mut trainer = Box::new(args.trainer());
+            if trainer.config.evaluation == ga::Evaluation::Isolated {
+                exit_with("--live shows a shared world; set ga.evaluation to \"shared\" or train headless");
+            }
+            let output: Option<RunOutput> = args.output(&trainer);
+            let title = format!("Environment (seed {})", trainer.seed);
+            let world = trainer.start_generation();
+            (world, Mode::Train {trainer, output}, title)
+        }
+        Command::Watch {champion, scenario} => {
+            let (config, seed) = scenario.load();
+            let world = watch(&champion, config, seed);
+            (world, Mode::Watch, format!("Watching {} (seed {})", champion.display(), seed))
+        }
+        Command::Replay {recording} => {
+            let title = format!("Replay of {}", recording.display());
+            let recording = Recording::load(&recording).unwrap_or_else(|error| exit_with(error));
+            (recording.world(), Mode::Replay {recording, frame: 0}, title)
+        }
+    };
+    // the window matches the arena's shape, one pixel per unit unless that makes it too large
+    let scale: f32 = (MAX_WINDOW_SIZE / world.bounds.w().max(world.bounds.h())).min(1.);
+    let (width, height) = (world.bounds.w() * scale, world.bounds.h() * scale);
     app
         .new_window()
-        .size(512, 512)
-        .title("Environment")
+        .size((width as u32).max(1), (height as u32).max(1))
+        .title(title)
         .view(view)
+        .key_pressed(key_pressed)
+        .mouse_moved(mouse_moved)
+        .mouse_wheel(mouse_wheel)
         .build()
         .unwrap();
-    Model {world: world::setup_world()}
+    let camera = Camera::new(world.bounds);
+    Model {
+        world,
+        mode,
+        view_options: ViewOptions::default(),
+        selected: None,
+        playback: Playback::default(),
+        camera,
+        pointer: Vec2::ZERO,
+    }
+}
+
+/// Sets up a world with a single player steered by a saved champion.
+/// Exits the program if the champion can't be loaded.
+///
+/// Arguments
+/// * `path`: the champion's file.
+/// * `config`: the scenario to watch it in.
+/// * `seed`: seed of the world.
+fn watch(path: &Path, config: Config, seed: u64) -> World {
+    let champion = Champion::load(path).unwrap_or_else(|error| exit_with(error));
+    if !champion.trained_with(&config) {
+        eprintln!("note: {} was trained with a different config", path.display());
+    }
+    println!(
+        "{}: fitness {} in generation {} of a run with seed {}",
+        path.display(),
+        champion.genome.fitness,
+        champion.generation,
+        champion.seed,
+    );
+    World::with_players(config.world, seed, vec![champion.player()])
 }
 
 /// Called after every update.
-fn update(app: &App, model: &mut Model, _update: Update) {
-    world::update(app, &mut model.world);
+/// Advances the world by as many steps as the playback speed calls for, each by the frame's
+/// time but never more than [`MAX_FRAME_DT`].
+fn update(app: &App, model: &mut Model, update: Update) {
+    let Model {world, mode, selected, playback, camera, ..} = model;
+    let input = controls(app, camera);
+    let dt = update.since_last.as_secs_f32().min(MAX_FRAME_DT);
+    playback.run(|| step(app, world, mode, input, dt));
+    follow_selection(world, selected);
+}
+
+/// Advances the world by a single step.
+/// Brains are stepped by the same fixed step as headless training, and when training live the
+/// next generation is bred as soon as the current one has run its course.
+///
+/// Arguments
+/// * `app`: nannou::app instance.
+/// * `world`, `mode`: the app's state.
+/// * `input`: what the user is doing this frame.
+/// * `dt`: time in seconds since the previous frame.
+fn step(app: &App, world: &mut World, mode: &mut Mode, input: Input, dt: f32) {
+    match mode {
+        Mode::Play {recording} => {
+            if let Some((_, recording)) = recording {
+                recording.record(input, dt);
+            }
+            world.step(&input, dt);
+        }
+        Mode::Train {trainer, output} => {
+            world.step(&Input::default(), ga::DT);
+            if trainer.is_finished(world) {
+                let champion = trainer.finish_generation(world);
+                app.main_window().set_title(&format!(
+                    "Environment (generation {}, last best fitness {})",
+                    trainer.population.generation,
+                    champion.fitness,
+                ));
+                if let Some(output) = output {
+                    output.generation_done(trainer, champion);
+                }
+                *world = trainer.start_generation();
+            }
+        }
+        Mode::Watch => {
+            let input = Input {target: None, ..input};
+            world.step(&input, ga::DT);
+        }
+        Mode::Replay {recording, frame} => {
+            if let Some(recording::Frame {input, dt}) = recording.frames.get(*frame) {
+                world.step(input, *dt);
+                *frame += 1;
+            }
+        }
+    }
+}
+
+/// Starts over right away: a fresh world, the current generation again when training, or the
+/// recording from its first frame.
+fn restart(app: &App, model: &mut Model) {
+    let Model {world, mode, camera, ..} = model;
+    match mode {
+        Mode::Play {recording} => {
+            // the reset goes through a step that takes no time, so recordings replay it too
+            let input = Input {reset: true, ..controls(app, camera)};
+            if let Some((_, recording)) = recording {
+                recording.record(input, 0.);
+            }
+            world.step(&input, 0.);
+        }
+        Mode::Train {trainer, ..} => *world = trainer.start_generation(),
+        Mode::Watch => world.reset(),
+        Mode::Replay {recording, frame} => {
+            *world = recording.world();
+            *frame = 0;
+        }
+    }
+}
+
+/// Whether a player's brain can be drawn and is still at work.
+fn selectable(world: &World, index: usize) -> bool {
+    world.players.get(index).is_some_and(|player| player.alive && player.brain.is_some())
+}
+
+/// The next living player with a brain after the given one, wrapping around.
+fn next_selectable(world: &World, after: Option<usize>) -> Option<usize> {
+    let len: usize = world.players.len();
+    let start: usize = after.map_or(0, |index| index + 1);
+    (0..len)
+        .map(|offset| (start + offset) % len)
+        .find(|&index| selectable(world, index))
+}
+
+/// Moves the selection on to another living player with a brain once the selected one dies.
+/// The last player standing stays selected after it dies too.
+fn follow_selection(world: &World, selected: &mut Option<usize>) {
+    if !selected.is_some_and(|index| selectable(world, index)) {
+        if let Some(next) = next_selectable(world, *selected) {
+            *selected = Some(next);
+        }
+    }
+}
+
+/// Saves the session when the window closes, if it was being recorded.
+fn exit(_app: &App, model: Model) {
+    if let Mode::Play {recording: Some((path, recording))} = model.mode {
+        match recording.save(&path) {
+            Ok(()) => println!("saved recording to {}", path.display()),
+            Err(error) => eprintln!("couldn't save recording: {}", error),
+        }
+    }
+}
+
+/// User inputs to control world attributes
+///
+/// Arguments
+/// * `app`: nannou::app instance.
+/// * `camera`: how the window looks at the world, to find the cursor in it.
+fn controls(app: &App, camera: &Camera) -> Input {
+    let cursor: Vec2 = camera.to_world(&app.window_rect(), app.mouse.position());
+    Input {
+        // Mouse: Player follows the cursor
+        target: Some(Position {x: cursor.x, y: cursor.y}),
+        // Left Click: Restart
+        restart: app.mouse.buttons.left().is_down(),
+        ..Default::default()
+    }
+}
+
+/// Keyboard controls for the simulation and toggles for what gets drawn.
+fn key_pressed(app: &App, model: &mut Model, key: Key) {
+    match key {
+        // Space: Pause or resume
+        Key::Space => model.playback.toggle_pause(),
+        // Period or Right: Pause and advance by a single step
+        Key::Period | Key::Right => model.playback.request_step(),
+        // 1 to 4: Simulate 1, 4, 16 or as many steps as fit per frame
+        Key::Key1 => model.playback.speed = Speed::Normal,
+        Key::Key2 => model.playback.speed = Speed::Fast,
+        Key::Key3 => model.playback.speed = Speed::Faster,
+        Key::Key4 => model.playback.speed = Speed::Max,
+        // R: Restart
+        Key::R => restart(app, model),
+        // C: Frame the whole arena again
+        Key::C => model.camera.reset(),
+        // V: Toggle vision rays
+        Key::V => model.view_options.rays = !model.view_options.rays,
+        // H: Toggle the HUD
+        Key::H => model.view_options.hud = !model.view_options.hud,
+        // G: Toggle the fitness chart
+        Key::G => model.view_options.chart = !model.view_options.chart,
+        // B: Toggle the selected player's brain
+        Key::B => model.view_options.network = !model.view_options.network,
+        // N: Select the next living player with a brain
+        Key::N => {
+            if let Some(next) = next_selectable(&model.world, model.selected) {
+                model.selected = Some(next);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Pans the camera while the right mouse button is held.
+fn mouse_moved(app: &App, model: &mut Model, position: Point2) {
+    if app.mouse.buttons.right().is_down() {
+        model.camera.pan(&app.window_rect(), position - model.pointer);
+    }
+    model.pointer = position;
+}
+
+/// Zooms the camera around the cursor.
+fn mouse_wheel(app: &App, model: &mut Model, delta: MouseScrollDelta, _phase: TouchPhase) {
+    let lines: f32 = match delta {
+        MouseScrollDelta::LineDelta(_, y) => y,
+        MouseScrollDelta::PixelDelta(position) => position.y as f32 / 40.,
+    };
+    model.camera.zoom_at(&app.window_rect(), app.mouse.position(), 1.15_f32.powf(lines));
+}
+
+/// The numbers shown on the HUD.
+fn hud_stats(app: &App, model: &Model) -> HudStats {
+    let Model {world, mode, ..} = model;
+    let trainer: Option<&Trainer> = match mode {
+        Mode::Train {trainer, ..} => Some(trainer),
+        _ => None,
+    };
+    HudStats {
+        generation: trainer.map(|trainer| trainer.population.generation),
+        alive: world.alive(),
+        players: world.players.len(),
+        reached_goal: world.config.goal.map(|_| {
+            world.players.iter().filter(|player| player.stats.reached_goal.is_some()).count()
+        }),
+        ticks: world.ticks,
+        best_fitness: trainer.and_then(|trainer| trainer.history.iter().map(|stats| stats.best).reduce(f32::max)),
+        fps: app.fps(),
+        playback: model.playback,
+        seed: trainer.map_or(world.seed, |trainer| trainer.seed),
+    }
+}
+
+/// Circles the selected player and draws its brain, fed with what the player currently sees.
+///
+/// Arguments
+/// * `app`: nannou::app instance.
+/// * `draw`: nannou::draw instance, in window coordinates.
+/// * `scene`: the same, in world coordinates.
+/// * `model`: the app's state.
+fn draw_selected_brain(app: &App, draw: &Draw, scene: &Draw, model: &Model) {
+    let Some(index) = model.selected else {
+        return;
+    };
+    let Some(player) = model.world.players.get(index) else {
+        return;
+    };
+    let Some(brain) = &player.brain else {
+        return;
+    };
+    scene.ellipse()
+        .x_y(player.position.x, player.position.y)
+        .radius(player.radius * 2.)
+        .no_fill()
+        .stroke_weight(1.5)
+        .stroke(YELLOW);
+    // before the first step the player hasn't looked around yet
+    let inputs: Vec<f32> = match player.sight.is_empty() {
+        true => vec![0.; brain.topology.layers[0]],
+        false => player.vision.inputs(&player.sight),
+    };
+    let title = format!("player {} ({})", index, if player.alive { "alive" } else { "dead" });
+    network_view::draw_network(draw, &app.window_rect(), brain, &brain.activations(&inputs), &title);
 }
 
 /// Draw entities to the canvas.
 fn view(app: &App, model: &Model, frame: Frame) {
     let draw = app.draw();
     draw.background().color(DARKSLATEGRAY);
-    world::draw_view(&draw, &model.world);
+    let scene = model.camera.transform(&draw, &app.window_rect());
+    world::draw_view(&scene, &model.world, &model.view_options);
+    if model.view_options.hud {
+        hud::draw_hud(&draw, &app.window_rect(), &hud_stats(app, model));
+    }
+    if let (Mode::Train {trainer, ..}, true) = (&model.mode, model.view_options.chart) {
+        chart::draw_fitness_chart(&draw, &app.window_rect(), &trainer.history);
+    }
+    if model.view_options.network {
+        draw_selected_brain(app, &draw, &scene, model);
+    }
     draw.to_frame(app, &frame).unwrap();
-}
\ No newline at end of file
+}
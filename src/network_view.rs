@@ -0,0 +1,106 @@
+//! A picture of a brain at work.
+//!
+//! [`draw_network`] lays the network out in columns, one per layer from the inputs on the left to
+//! the outputs on the right. Every connection is a line colored by its weight's sign (green for
+//! positive, red for negative) and as opaque as the weight is strong. Every neuron is a dot
+//! colored by its current activation (yellow for positive, blue for negative), so dead sensors and
+//! saturated neurons stand out at a glance.
+
+use nannou::{
+	color::Rgba,
+	geom::{Rect, Vec2},
+	Draw,
+};
+
+use crate::{
+	nn::Network,
+	panel::{draw_panel, Corner, LABEL_HEIGHT, MARGIN},
+};
+
+/// Width of the panel.
+const PANEL_WIDTH: f32 = 220.;
+
+/// Vertical distance between the neurons of a layer.
+const NODE_SPACING: f32 = 11.;
+
+/// Radius of a neuron's dot.
+const NODE_RADIUS: f32 = 3.5;
+
+/// Draws a network and its current activations on a translucent panel in the top right corner
+/// of the window.
+///
+/// Arguments
+/// * `draw`: nannou::draw instance.
+/// * `window`: the window's rectangle.
+/// * `network`: the brain to draw.
+/// * `activations`: what every layer currently produces, as returned by [`Network::activations`].
+/// * `title`: shown above the network, e.g. which player it belongs to.
+pub fn draw_network(draw: &Draw, window: &Rect, network: &Network, activations: &[Vec<f32>], title: &str) {
+	let layers: &[usize] = &network.topology.layers;
+	let tallest: usize = layers.iter().copied().max().unwrap_or(0);
+	let plot_height: f32 = tallest.saturating_sub(1) as f32 * NODE_SPACING;
+	let height: f32 = plot_height + NODE_RADIUS * 2. + MARGIN * 3. + LABEL_HEIGHT;
+	let panel: Rect = draw_panel(draw, window, Corner::TopRight, PANEL_WIDTH, height);
+	let title_area = Rect::from_w_h(PANEL_WIDTH - MARGIN * 2., LABEL_HEIGHT)
+		.top_left_of(panel)
+		.shift_x(MARGIN)
+		.shift_y(-MARGIN);
+	draw.text(title)
+		.xy(title_area.xy())
+		.wh(title_area.wh())
+		.font_size(11)
+		.left_justify()
+		.color(Rgba::new(1.0, 1.0, 1.0, 0.9));
+	let plot = Rect::from_w_h(PANEL_WIDTH - MARGIN * 2. - NODE_RADIUS * 2., plot_height)
+		.mid_bottom_of(panel)
+		.shift_y(MARGIN + NODE_RADIUS);
+	// where every neuron of every layer sits, each layer centered vertically
+	let positions: Vec<Vec<Vec2>> = layers
+		.iter()
+		.enumerate()
+		.map(|(column, &count)| {
+			let x: f32 = plot.left() + plot.w() * column as f32 / (layers.len() - 1).max(1) as f32;
+			let top: f32 = plot.y() + count.saturating_sub(1) as f32 * NODE_SPACING / 2.;
+			(0..count).map(|row| Vec2::new(x, top - row as f32 * NODE_SPACING)).collect()
+		})
+		.collect();
+	let strongest: f32 = network
+		.layers
+		.iter()
+		.flat_map(|layer| layer.neurons.iter())
+		.flat_map(|neuron| neuron.weights.iter())
+		.fold(f32::EPSILON, |strongest, weight| strongest.max(weight.abs()));
+	for (index, layer) in network.layers.iter().enumerate() {
+		for (neuron, end) in layer.neurons.iter().zip(&positions[index + 1]) {
+			for (weight, start) in neuron.weights.iter().zip(&positions[index]) {
+				let alpha: f32 = 0.05 + 0.75 * weight.abs() / strongest;
+				let color: Rgba = match *weight >= 0. {
+					true => Rgba::new(0.3, 0.9, 0.4, alpha),
+					false => Rgba::new(1.0, 0.35, 0.35, alpha),
+				};
+				draw.line()
+					.start(*start)
+					.end(*end)
+					.weight(1.0)
+					.color(color);
+			}
+		}
+	}
+	for (column, layer) in positions.iter().enumerate() {
+		for (row, position) in layer.iter().enumerate() {
+			let activation: f32 = activations
+				.get(column)
+				.and_then(|values| values.get(row))
+				.copied()
+				.unwrap_or(0.)
+				.clamp(-1., 1.);
+			let (positive, negative) = (activation.max(0.), (-activation).max(0.));
+			draw.ellipse()
+				.xy(*position)
+				.radius(NODE_RADIUS)
+				.color(Rgba::new(0.15 + 0.85 * positive, 0.15 + 0.7 * positive, 0.15 + 0.85 * negative, 1.0))
+				.stroke_weight(0.5)
+				.stroke(Rgba::new(1.0, 1.0, 1.0, 0.5));
+		}
+	}
+}
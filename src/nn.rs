@@ -0,0 +1,189 @@
+//! A small feed-forward neural network (multilayer perceptron) used as the Player's brain.
+//!
+//! A network is a stack of fully connected layers. Every neuron sums its weighted inputs, adds its
+//! bias and passes the result through the layer's activation function. The shape of the network
+//! is described by a [`Topology`], and all of its weights and biases can be flattened into (and
+//! rebuilt from) a single list of numbers, which is what the genetic algorithm evolves.
+
+use nannou::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Squashes a neuron's weighted sum into its output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+	/// Passes the sum through unchanged.
+	Identity,
+	/// Clamps negative sums to zero.
+	Relu,
+	/// Maps the sum into `(0, 1)`.
+	Sigmoid,
+	/// Maps the sum into `(-1, 1)`.
+	Tanh,
+}
+
+impl Activation {
+	/// Applies the activation function to a neuron's weighted sum.
+	pub fn apply(self, x: f32) -> f32 {
+		match self {
+			Activation::Identity => x,
+			Activation::Relu => x.max(0.),
+			Activation::Sigmoid => 1. / (1. + (-x).exp()),
+			Activation::Tanh => x.tanh(),
+		}
+	}
+}
+
+/// Describes the shape of a network.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Topology {
+	/// Number of neurons in each layer, starting with the inputs and ending with the outputs.
+	pub layers: Vec<usize>,
+	/// Activation used by every hidden layer.
+	pub hidden: Activation,
+	/// Activation used by the output layer.
+	pub output: Activation,
+}
+
+impl Topology {
+	/// Number of weights and biases a network of this shape has.
+	pub fn weight_count(&self) -> usize {
+		self.layers
+			.windows(2)
+			.map(|pair| (pair[0] + 1) * pair[1])
+			.sum()
+	}
+
+	/// Activation used by the layer at `index` (not counting the input layer).
+	fn activation(&self, index: usize) -> Activation {
+		match index + 2 == self.layers.len() {
+			true => self.output,
+			false => self.hidden,
+		}
+	}
+}
+
+/// A fully connected feed-forward network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+	pub topology: Topology,
+	pub layers: Vec<Layer>,
+}
+
+/// A set of neurons that all read the previous layer's outputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer {
+	pub neurons: Vec<Neuron>,
+	pub activation: Activation,
+}
+
+/// A single neuron: one weight per input and a bias.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Neuron {
+	pub bias: f32,
+	pub weights: Vec<f32>,
+}
+
+impl Network {
+	/// Creates a network with weights and biases drawn uniformly from `[-1, 1]`.
+	///
+	/// Arguments
+	/// * `rng`: random number generator to draw the weights from.
+	/// * `topology`: the shape of the network.
+	pub fn random(rng: &mut impl Rng, topology: &Topology) -> Self {
+		let weights: Vec<f32> = (0..topology.weight_count())
+			.map(|_| rng.gen_range(-1.0..=1.0))
+			.collect();
+		Self::from_weights(topology, weights)
+	}
+
+	/// Rebuilds a network from a flat list of weights, as produced by [`Network::weights`].
+	///
+	/// Arguments
+	/// * `topology`: the shape of the network.
+	/// * `weights`: for each neuron of each layer, its bias followed by its input weights.
+	///
+	/// Panics if the number of weights doesn't match [`Topology::weight_count`].
+	pub fn from_weights(topology: &Topology, weights: impl IntoIterator<Item = f32>) -> Self {
+		assert!(topology.layers.len() > 1, "a network needs at least an input and an output layer");
+		let mut weights = weights.into_iter();
+		let layers: Vec<Layer> = topology.layers
+			.windows(2)
+			.enumerate()
+			.map(|(index, pair)| Layer {
+				neurons: (0..pair[1])
+					.map(|_| Neuron {
+						bias: weights.next().expect("not enough weights for topology"),
+						weights: (0..pair[0])
+							.map(|_| weights.next().expect("not enough weights for topology"))
+							.collect(),
+					})
+					.collect(),
+				activation: topology.activation(index),
+			})
+			.collect();
+		assert!(weights.next().is_none(), "too many weights for topology");
+		Self {topology: topology.clone(), layers}
+	}
+
+	/// Flattens every bias and weight of the network into a single list.
+	pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+		self.layers
+			.iter()
+			.flat_map(|layer| layer.neurons.iter())
+			.flat_map(|neuron| std::iter::once(neuron.bias).chain(neuron.weights.iter().copied()))
+	}
+
+	/// Feeds inputs through every layer of the network.
+	///
+	/// Arguments
+	/// * `inputs`: one value per neuron of the input layer.
+	///
+	/// Returns
+	/// * `outputs`: one value per neuron of the output layer.
+	pub fn propagate(&self, inputs: &[f32]) -> Vec<f32> {
+		assert_eq!(inputs.len(), self.topology.layers[0], "wrong number of network inputs");
+		self.layers
+			.iter()
+			.fold(inputs.to_vec(), |inputs, layer| layer.propagate(&inputs))
+	}
+
+	/// Feeds inputs through every layer of the network, keeping what every layer produced.
+	///
+	/// Arguments
+	/// * `inputs`: one value per neuron of the input layer.
+	///
+	/// Returns
+	/// * `activations`: one list per layer of the topology: the inputs, then the outputs of every
+	///   layer. The last list is what [`Network::propagate`] returns.
+	pub fn activations(&self, inputs: &[f32]) -> Vec<Vec<f32>> {
+		assert_eq!(inputs.len(), self.topology.layers[0], "wrong number of network inputs");
+		let mut activations: Vec<Vec<f32>> = vec![inputs.to_vec()];
+		for layer in &self.layers {
+			let outputs: Vec<f32> = layer.propagate(activations.last().unwrap());
+			activations.push(outputs);
+		}
+		activations
+	}
+}
+
+impl Layer {
+	/// Computes the output of every neuron in the layer.
+	fn propagate(&self, inputs: &[f32]) -> Vec<f32> {
+		self.neurons
+			.iter()
+			.map(|neuron| self.activation.apply(neuron.sum(inputs)))
+			.collect()
+	}
+}
+
+impl Neuron {
+	/// Weighted sum of the inputs plus the bias, before activation.
+	fn sum(&self, inputs: &[f32]) -> f32 {
+		inputs
+			.iter()
+			.zip(&self.weights)
+			.map(|(input, weight)| input * weight)
+			.sum::<f32>() + self.bias
+	}
+}
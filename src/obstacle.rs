@@ -0,0 +1,103 @@
+//! Static walls and blocks that shape the arena.
+//!
+//! An [`Obstacle`] never moves. Players and enemies that run into one are pushed back out and
+//! lose the part of their velocity that points into it, and a lethal obstacle also kills the
+//! players that touch it. Players see obstacles like they see the arena wall, so brains can learn
+//! to find their way through corridors and mazes.
+
+use nannou::geom::{Rect, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	collision::{circle_polygon, circle_rect, circle_segment, polygon_edges, Circle, Contact, Segment},
+	world::Position,
+};
+
+/// The outline of an obstacle, in world coordinates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum Shape {
+	/// An axis-aligned rectangle.
+	Rect {center: [f32; 2], size: [f32; 2]},
+	/// A thin wall between two points.
+	Segment {start: [f32; 2], end: [f32; 2]},
+	/// A polygon with at least three corners, listed in order.
+	Polygon {points: Vec<[f32; 2]>},
+}
+
+/// Something in the way.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Obstacle {
+	#[serde(flatten)]
+	pub shape: Shape,
+	/// Whether touching the obstacle kills a player. Enemies are only blocked.
+	#[serde(default)]
+	pub lethal: bool,
+}
+
+impl Shape {
+	/// The rectangle of a [`Shape::Rect`].
+	fn rect(center: [f32; 2], size: [f32; 2]) -> Rect {
+		Rect::from_x_y_w_h(center[0], center[1], size[0], size[1])
+	}
+
+	/// The corners of a [`Shape::Polygon`].
+	fn points(points: &[[f32; 2]]) -> Vec<Vec2> {
+		points.iter().map(|point| Vec2::from(*point)).collect()
+	}
+}
+
+impl Obstacle {
+	/// How a circle overlaps the obstacle, `None` if it doesn't.
+	pub fn contact(&self, circle: &Circle) -> Option<Contact> {
+		match &self.shape {
+			Shape::Rect {center, size} => circle_rect(circle, &Shape::rect(*center, *size)),
+			Shape::Segment {start, end} => {
+				circle_segment(circle, &Segment {start: Vec2::from(*start), end: Vec2::from(*end)})
+			}
+			Shape::Polygon {points} => circle_polygon(circle, &Shape::points(points)),
+		}
+	}
+
+	/// The line segments making up the obstacle's outline, for casting rays against.
+	pub fn edges(&self) -> Vec<Segment> {
+		match &self.shape {
+			Shape::Rect {center, size} => {
+				let rect: Rect = Shape::rect(*center, *size);
+				let corners: [Vec2; 4] = [
+					Vec2::new(rect.left(), rect.bottom()),
+					Vec2::new(rect.right(), rect.bottom()),
+					Vec2::new(rect.right(), rect.top()),
+					Vec2::new(rect.left(), rect.top()),
+				];
+				polygon_edges(&corners).collect()
+			}
+			Shape::Segment {start, end} => vec![Segment {start: Vec2::from(*start), end: Vec2::from(*end)}],
+			Shape::Polygon {points} => polygon_edges(&Shape::points(points)).collect(),
+		}
+	}
+}
+
+/// Pushes a circular entity out of every obstacle it overlaps.
+/// The entity loses the part of its velocity that points into the obstacles.
+///
+/// Arguments
+/// * `obstacles`: the obstacles in the world.
+/// * `position`: the entity's position.
+/// * `radius`: the entity's radius.
+/// * `velocity`: the entity's velocity.
+///
+/// Returns
+/// * `lethal`: whether the entity touched a lethal obstacle.
+pub fn resolve(obstacles: &[Obstacle], position: &mut Position, radius: f32, velocity: &mut Vec2) -> bool {
+	let mut lethal: bool = false;
+	for obstacle in obstacles {
+		let circle = Circle {center: (*position).into(), radius};
+		if let Some(Contact {normal, penetration, ..}) = obstacle.contact(&circle) {
+			*position = (circle.center + normal * penetration).into();
+			*velocity -= normal * velocity.dot(normal).min(0.);
+			lethal |= obstacle.lethal;
+		}
+	}
+	lethal
+}
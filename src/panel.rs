@@ -0,0 +1,47 @@
+//! The translucent panels the overlays are drawn on.
+//!
+//! The HUD, the fitness chart and the brain view each sit in a corner of the window on a dark,
+//! see-through background, so the world stays visible underneath. [`draw_panel`] places and draws
+//! that background, and the overlays lay out their contents inside the rectangle it returns.
+
+use nannou::{color::Rgba, geom::Rect, Draw};
+
+/// Gap between a panel and the window's edges, and between the panel's edges and its contents.
+pub const MARGIN: f32 = 8.;
+
+/// Height of a row of small text, like a title or a label.
+pub const LABEL_HEIGHT: f32 = 14.;
+
+/// A corner of the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+	TopLeft,
+	TopRight,
+	BottomLeft,
+	BottomRight,
+}
+
+/// Draws the background of a panel in a corner of the window, [`MARGIN`] away from its edges.
+///
+/// Arguments
+/// * `draw`: nannou::draw instance.
+/// * `window`: the window's rectangle.
+/// * `corner`: where the panel goes.
+/// * `width`, `height`: the panel's size.
+///
+/// Returns
+/// * `panel`: the panel's rectangle, for laying out its contents.
+pub fn draw_panel(draw: &Draw, window: &Rect, corner: Corner, width: f32, height: f32) -> Rect {
+	let panel = Rect::from_w_h(width, height);
+	let panel: Rect = match corner {
+		Corner::TopLeft => panel.top_left_of(*window).shift_x(MARGIN).shift_y(-MARGIN),
+		Corner::TopRight => panel.top_right_of(*window).shift_x(-MARGIN).shift_y(-MARGIN),
+		Corner::BottomLeft => panel.bottom_left_of(*window).shift_x(MARGIN).shift_y(MARGIN),
+		Corner::BottomRight => panel.bottom_right_of(*window).shift_x(-MARGIN).shift_y(MARGIN),
+	};
+	draw.rect()
+		.xy(panel.xy())
+		.wh(panel.wh())
+		.color(Rgba::new(0.0, 0.0, 0.0, 0.5));
+	panel
+}
@@ -0,0 +1,74 @@
+//! Movement for every entity, following the vector model of The Nature of Code.
+//!
+//! Entities don't move directly. Forces are applied to them during a step, accumulate into an
+//! acceleration (scaled by mass), and [`Motion::integrate`] turns that into velocity and position
+//! using the step's delta time, so movement is the same at any frame rate.
+
+use nannou::geom::Vec2;
+
+use crate::world::Position;
+
+/// How quickly steering corrects the difference between the desired and the current velocity,
+/// per second.
+pub const STEERING_RESPONSE: f32 = 10.;
+
+/// The kinematic state of an entity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Motion {
+	/// Distance per second.
+	pub velocity: Vec2,
+	/// Sum of the forces applied since the last integration, divided by mass.
+	pub acceleration: Vec2,
+	/// Speed the entity can never exceed.
+	pub max_speed: f32,
+	/// Strength of the strongest steering force the entity can produce.
+	pub max_force: f32,
+	pub mass: f32,
+}
+
+impl Motion {
+	/// Creates a motionless state.
+	///
+	/// Arguments
+	/// * `max_speed`: speed the entity can never exceed.
+	/// * `max_force`: strength of the strongest steering force the entity can produce.
+	/// * `mass`: how strongly the entity resists forces.
+	pub fn new(max_speed: f32, max_force: f32, mass: f32) -> Self {
+		Self {velocity: Vec2::ZERO, acceleration: Vec2::ZERO, max_speed, max_force, mass}
+	}
+
+	/// Adds a force to apply during the next integration.
+	pub fn apply_force(&mut self, force: Vec2) {
+		self.acceleration += force / self.mass;
+	}
+
+	/// Advances velocity and position by one step (semi-implicit Euler) and clears the
+	/// accumulated forces.
+	///
+	/// Arguments
+	/// * `position`: the entity's position, moved in place.
+	/// * `dt`: time in seconds since the previous step.
+	pub fn integrate(&mut self, position: &mut Position, dt: f32) {
+		self.velocity = (self.velocity + self.acceleration * dt).clamp_length_max(self.max_speed);
+		*position = (Vec2::from(*position) + self.velocity * dt).into();
+		self.acceleration = Vec2::ZERO;
+	}
+
+	/// Steering force that brings the entity to a stop at a target, slowing down as it
+	/// gets within `slowing_radius` ("arrive" in The Nature of Code).
+	///
+	/// Arguments
+	/// * `position`: where the entity is.
+	/// * `target`: where it wants to be.
+	/// * `slowing_radius`: distance from the target at which the entity starts braking.
+	pub fn arrive(&self, position: Vec2, target: Vec2, slowing_radius: f32) -> Vec2 {
+		let offset: Vec2 = target - position;
+		let distance: f32 = offset.length();
+		let speed: f32 = match distance < slowing_radius {
+			true => self.max_speed * distance / slowing_radius,
+			false => self.max_speed,
+		};
+		let desired: Vec2 = offset.normalize_or_zero() * speed;
+		((desired - self.velocity) * self.mass * STEERING_RESPONSE).clamp_length_max(self.max_force)
+	}
+}
@@ -0,0 +1,100 @@
+//! How fast a front-end advances its world.
+//!
+//! Watching a run in real time is slow, so a [`Playback`] decides how many steps to simulate for
+//! every rendered frame: none while paused (unless a single step was asked for), a fixed multiple
+//! of one step, or as many as fit in a frame.
+
+use std::time::{Duration, Instant};
+
+/// Real time the [`Speed::Max`] setting spends simulating per frame, leaving the rest for drawing.
+pub const MAX_SPEED_BUDGET: Duration = Duration::from_millis(12);
+
+/// Number of steps simulated per rendered frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Speed {
+	/// One step per frame.
+	#[default]
+	Normal,
+	/// Four steps per frame.
+	Fast,
+	/// Sixteen steps per frame.
+	Faster,
+	/// As many steps as fit in [`MAX_SPEED_BUDGET`].
+	Max,
+}
+
+impl Speed {
+	/// Number of steps per frame, `None` for as many as fit.
+	pub fn steps_per_frame(self) -> Option<usize> {
+		match self {
+			Speed::Normal => Some(1),
+			Speed::Fast => Some(4),
+			Speed::Faster => Some(16),
+			Speed::Max => None,
+		}
+	}
+
+	/// Short label for the speed, e.g. `4x`.
+	pub fn label(self) -> &'static str {
+		match self {
+			Speed::Normal => "1x",
+			Speed::Fast => "4x",
+			Speed::Faster => "16x",
+			Speed::Max => "max",
+		}
+	}
+}
+
+/// Pause state and speed of a front-end's simulation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Playback {
+	pub paused: bool,
+	pub speed: Speed,
+	/// Whether a single step was asked for while paused.
+	step_requested: bool,
+}
+
+impl Playback {
+	/// Pauses a running simulation or resumes a paused one.
+	pub fn toggle_pause(&mut self) {
+		self.paused = !self.paused;
+	}
+
+	/// Pauses the simulation and has it advance by exactly one step on the next frame.
+	pub fn request_step(&mut self) {
+		self.paused = true;
+		self.step_requested = true;
+	}
+
+	/// Simulates the steps due this frame.
+	///
+	/// Arguments
+	/// * `step`: advances the simulation by one step.
+	///
+	/// Returns
+	/// * `steps`: number of steps simulated.
+	pub fn run(&mut self, mut step: impl FnMut()) -> usize {
+		if self.paused {
+			let step_requested: bool = std::mem::take(&mut self.step_requested);
+			if step_requested {
+				step();
+			}
+			return step_requested as usize;
+		}
+		match self.speed.steps_per_frame() {
+			Some(steps) => {
+				(0..steps).for_each(|_| step());
+				steps
+			}
+			None => {
+				let started = Instant::now();
+				let mut steps: usize = 0;
+				while steps == 0 || started.elapsed() < MAX_SPEED_BUDGET {
+					step();
+					steps += 1;
+				}
+				steps
+			}
+		}
+	}
+}
@@ -0,0 +1,62 @@
+//! Recordings of interactive sessions.
+//!
+//! A world is fully determined by its config, its seed, and the input and delta time of every
+//! step, so that is all a [`Recording`] keeps. Stepping a fresh world through the same frames
+//! reproduces the session exactly.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	config::WorldConfig,
+	storage::{self, StorageError},
+	world::{Input, World},
+};
+
+/// One step of a recorded session.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+	pub input: Input,
+	/// Time in seconds since the previous step.
+	pub dt: f32,
+}
+
+/// Everything needed to play a session back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+	pub config: WorldConfig,
+	pub seed: u64,
+	pub frames: Vec<Frame>,
+}
+
+impl Recording {
+	/// Starts an empty recording of a world that is about to be stepped.
+	pub fn new(world: &World) -> Self {
+		Self {config: world.config.clone(), seed: world.seed, frames: Vec::new()}
+	}
+
+	/// Records one step.
+	///
+	/// Arguments
+	/// * `input`: what the world was told during the step.
+	/// * `dt`: time in seconds since the previous step.
+	pub fn record(&mut self, input: Input, dt: f32) {
+		self.frames.push(Frame {input, dt});
+	}
+
+	/// The world as it was when recording started.
+	pub fn world(&self) -> World {
+		World::new(self.config.clone(), self.seed)
+	}
+
+	/// Reads a recording from a file.
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+		storage::read_json(path)
+	}
+
+	/// Writes the recording to a file.
+	pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StorageError> {
+		storage::write_json(path, self)
+	}
+}
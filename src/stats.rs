@@ -0,0 +1,163 @@
+//! Statistics about every generation of a training run.
+//!
+//! The trainer summarizes each evaluated generation in a [`GenerationStats`] and keeps them in its
+//! history. A [`StatsLog`] writes that history to a run directory as CSV and as JSON Lines, one
+//! row per generation, so learning curves can be plotted with any tool.
+
+use std::{
+	fs::{self, File},
+	io::{self, BufWriter, Write},
+	path::Path,
+	time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{fitness::EpisodeStats, ga::Population};
+
+/// Columns of the CSV log, in the order [`GenerationStats::csv_row`] writes them.
+const CSV_HEADER: &str =
+	"generation,best,mean,median,worst,std_dev,diversity,mean_survival_ticks,wall_time_secs,success_rate";
+
+/// How an evaluated generation did.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+	/// Number of generations bred before this one.
+	pub generation: usize,
+	/// Fitness of the generation's champion.
+	pub best: f32,
+	pub mean: f32,
+	pub median: f32,
+	pub worst: f32,
+	/// Standard deviation of the fitness.
+	pub std_dev: f32,
+	/// Standard deviation of every gene across the population, averaged over the genes.
+	/// Falls towards zero as the population converges.
+	pub diversity: f32,
+	/// Average number of ticks the players survived.
+	pub mean_survival_ticks: f32,
+	/// Real time spent evaluating and breeding the generation, in seconds.
+	pub wall_time_secs: f64,
+	/// Fraction of the players that reached the goal, `0` in scenarios without one.
+	#[serde(default)]
+	pub success_rate: f32,
+}
+
+impl GenerationStats {
+	/// Summarizes an evaluated population.
+	///
+	/// Arguments
+	/// * `population`: the generation, with every genome's fitness set.
+	/// * `episodes`: what happened to every genome's player.
+	/// * `wall_time`: real time spent on the generation.
+	pub fn new(population: &Population, episodes: &[EpisodeStats], wall_time: Duration) -> Self {
+		let mut fitness: Vec<f32> = population.genomes.iter().map(|genome| genome.fitness).collect();
+		fitness.sort_by(f32::total_cmp);
+		let mean: f32 = average(&fitness);
+		let variance: f32 = average(&fitness.iter().map(|value| (value - mean).powi(2)).collect::<Vec<f32>>());
+		let median: f32 = match fitness.len() {
+			0 => 0.,
+			len if len % 2 == 1 => fitness[len / 2],
+			len => (fitness[len / 2 - 1] + fitness[len / 2]) / 2.,
+		};
+		let survival: Vec<f32> = episodes.iter().map(|episode| episode.ticks_survived as f32).collect();
+		let successes: Vec<f32> = episodes
+			.iter()
+			.map(|episode| episode.reached_goal.is_some() as u8 as f32)
+			.collect();
+		Self {
+			generation: population.generation,
+			best: fitness.last().copied().unwrap_or(0.),
+			mean,
+			median,
+			worst: fitness.first().copied().unwrap_or(0.),
+			std_dev: variance.sqrt(),
+			diversity: diversity(population),
+			mean_survival_ticks: average(&survival),
+			wall_time_secs: wall_time.as_secs_f64(),
+			success_rate: average(&successes),
+		}
+	}
+
+	/// The stats as a line of the CSV log, without the line break.
+	pub fn csv_row(&self) -> String {
+		format!(
+			"{},{},{},{},{},{},{},{},{},{}",
+			self.generation,
+			self.best,
+			self.mean,
+			self.median,
+			self.worst,
+			self.std_dev,
+			self.diversity,
+			self.mean_survival_ticks,
+			self.wall_time_secs,
+			self.success_rate,
+		)
+	}
+}
+
+/// Average of some values, `0` if there are none.
+fn average(values: &[f32]) -> f32 {
+	match values.is_empty() {
+		true => 0.,
+		false => values.iter().sum::<f32>() / values.len() as f32,
+	}
+}
+
+/// Standard deviation of every gene across the population, averaged over the genes.
+fn diversity(population: &Population) -> f32 {
+	let genes: usize = population.genomes.first().map_or(0, |genome| genome.genes.len());
+	let deviations: Vec<f32> = (0..genes)
+		.map(|gene| {
+			let values: Vec<f32> = population.genomes.iter().map(|genome| genome.genes[gene]).collect();
+			let mean: f32 = average(&values);
+			average(&values.iter().map(|value| (value - mean).powi(2)).collect::<Vec<f32>>()).sqrt()
+		})
+		.collect();
+	average(&deviations)
+}
+
+/// Per-generation statistics of a run, written to `stats.csv` and `stats.jsonl`.
+pub struct StatsLog {
+	csv: BufWriter<File>,
+	jsonl: BufWriter<File>,
+}
+
+impl StatsLog {
+	/// Starts both logs in a run directory, replacing any previous ones.
+	///
+	/// Arguments
+	/// * `directory`: the run directory, created if needed.
+	/// * `history`: the generations evaluated so far, e.g. by a run that is being resumed.
+	///   Rows written after the checkpoint it was resumed from are dropped this way.
+	pub fn create(directory: &Path, history: &[GenerationStats]) -> io::Result<Self> {
+		fs::create_dir_all(directory)?;
+		let mut log = Self {
+			csv: BufWriter::new(File::create(directory.join("stats.csv"))?),
+			jsonl: BufWriter::new(File::create(directory.join("stats.jsonl"))?),
+		};
+		writeln!(log.csv, "{}", CSV_HEADER)?;
+		for stats in history {
+			log.write_row(stats)?;
+		}
+		log.flush()?;
+		Ok(log)
+	}
+
+	/// Appends a generation to both logs, flushing them so the rows survive an interruption.
+	pub fn append(&mut self, stats: &GenerationStats) -> io::Result<()> {
+		self.write_row(stats)?;
+		self.flush()
+	}
+
+	fn write_row(&mut self, stats: &GenerationStats) -> io::Result<()> {
+		writeln!(self.csv, "{}", stats.csv_row())?;
+		writeln!(self.jsonl, "{}", serde_json::to_string(stats)?)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.csv.flush()?;
+		self.jsonl.flush()
+	}
+}
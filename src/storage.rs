@@ -0,0 +1,98 @@
+//! Reading and writing the files a run leaves behind, such as champions and recordings.
+//!
+//! Everything is stored as JSON, so files can be inspected by hand and shared across machines.
+
+use std::{fmt, fs, io, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Why a file couldn't be read or written.
+#[derive(Debug)]
+pub enum StorageError {
+	/// The file couldn't be read or written.
+	Io(io::Error),
+	/// The file isn't valid JSON or doesn't hold what was expected.
+	Json(serde_json::Error),
+	/// The file was written in a format version this build can't read.
+	Version(u32),
+	/// The file is well formed but its contents don't add up.
+	Invalid(String),
+}
+
+impl fmt::Display for StorageError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			StorageError::Io(error) => write!(f, "couldn't access file: {}", error),
+			StorageError::Json(error) => write!(f, "invalid file: {}", error),
+			StorageError::Version(version) => write!(f, "unsupported format version {}", version),
+			StorageError::Invalid(reason) => write!(f, "invalid file: {}", reason),
+		}
+	}
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+	fn from(error: io::Error) -> Self {
+		StorageError::Io(error)
+	}
+}
+
+impl From<serde_json::Error> for StorageError {
+	fn from(error: serde_json::Error) -> Self {
+		StorageError::Json(error)
+	}
+}
+
+/// Reads a value from a JSON file.
+///
+/// Arguments
+/// * `path`: the file to read.
+///
+/// Returns
+/// * `value`: the value, or why it couldn't be read.
+pub fn read_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, StorageError> {
+	let text: String = fs::read_to_string(path)?;
+	Ok(serde_json::from_str(&text)?)
+}
+
+/// Reads a value from a JSON file with a top-level `version` field, refusing any version but the
+/// expected one before trying to make sense of the rest.
+///
+/// Arguments
+/// * `path`: the file to read.
+/// * `version`: the only format version that can be read.
+///
+/// Returns
+/// * `value`: the value, or why it couldn't be read.
+pub fn read_versioned_json<T: DeserializeOwned>(path: impl AsRef<Path>, version: u32) -> Result<T, StorageError> {
+	/// The part of every version of a format that says which version it is.
+	#[derive(Deserialize)]
+	struct Header {
+		version: u32,
+	}
+	let text: String = fs::read_to_string(path)?;
+	let Header {version: found} = serde_json::from_str(&text)?;
+	if found != version {
+		return Err(StorageError::Version(found));
+	}
+	Ok(serde_json::from_str(&text)?)
+}
+
+/// Writes a value to a JSON file, creating its directory if needed and replacing the file if it
+/// exists. The file is written next to its destination first and then moved in place, so an
+/// interrupted write never leaves a truncated file behind.
+///
+/// Arguments
+/// * `path`: the file to write.
+/// * `value`: what to write.
+pub fn write_json<T: Serialize>(path: impl AsRef<Path>, value: &T) -> Result<(), StorageError> {
+	let path: &Path = path.as_ref();
+	if let Some(directory) = path.parent() {
+		fs::create_dir_all(directory)?;
+	}
+	let partial = path.with_extension("partial");
+	fs::write(&partial, serde_json::to_string_pretty(value)?)?;
+	fs::rename(partial, path)?;
+	Ok(())
+}
@@ -0,0 +1,289 @@
+//! How a Player perceives the world: a fan of rays cast from its center.
+//!
+//! Every ray reports how close the nearest enemy and the nearest wall are along its direction,
+//! where walls are the arena's edge and any obstacles. A wrapping arena has no edge, and enemies
+//! across it are seen where they appear. Vision can also be told to look for food, which adds a
+//! third reading to every ray.
+//! Readings are normalized to `[0, 1]`, where `0` means nothing within range and `1` means
+//! touching, so they can be fed straight into a brain.
+
+use nannou::{
+	geom::{Rect, Vec2},
+	prelude::TAU,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	boundary::Space,
+	collision::Segment,
+	obstacle::Obstacle,
+	world::{Enemy, Food},
+};
+
+/// A configurable fan of rays.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Vision {
+	/// Number of rays in the fan.
+	pub rays: usize,
+	/// Angle covered by the fan in radians, centered on the player's heading.
+	/// A full circle spaces the rays evenly all around the player.
+	pub fov: f32,
+	/// Distance beyond which nothing is seen.
+	pub range: f32,
+	/// Whether the rays also look for food.
+	pub food: bool,
+}
+
+impl Default for Vision {
+	fn default() -> Self {
+		Self {rays: 8, fov: TAU, range: 150., food: false}
+	}
+}
+
+/// What a single ray saw. Every value is closeness in `[0, 1]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RayReading {
+	pub enemy: f32,
+	/// The arena's edge or an obstacle, whichever is closer.
+	pub wall: f32,
+	/// Always `0` unless the vision looks for food.
+	pub food: f32,
+}
+
+impl Vision {
+	/// Number of values [`Vision::inputs`] produces: two per ray, or three when looking for food.
+	pub fn input_len(&self) -> usize {
+		match self.food {
+			true => self.rays * 3,
+			false => self.rays * 2,
+		}
+	}
+
+	/// Angle of every ray in radians.
+	///
+	/// Arguments
+	/// * `heading`: the direction the player is facing, in radians.
+	pub fn angles(&self, heading: f32) -> impl Iterator<Item = f32> + '_ {
+		// a full circle would put the first and last ray on top of each other
+		let full_circle: bool = self.fov >= TAU;
+		let gaps: usize = match full_circle {
+			true => self.rays,
+			false => self.rays.saturating_sub(1).max(1),
+		};
+		let start: f32 = match (full_circle, self.rays) {
+			(_, 1) | (true, _) => heading,
+			(false, _) => heading - self.fov / 2.,
+		};
+		(0..self.rays).map(move |ray| start + self.fov * ray as f32 / gaps as f32)
+	}
+
+	/// Casts every ray from the given origin.
+	///
+	/// Arguments
+	/// * `origin`: where the rays start.
+	/// * `heading`: the direction the player is facing, in radians.
+	/// * `enemies`: the enemies that may be within range.
+	/// * `space`: the arena.
+	/// * `obstacles`: the obstacles in the arena.
+	/// * `food`: the food in the arena. Eaten items are ignored.
+	///
+	/// Returns
+	/// * `readings`: one reading per ray, in the order of [`Vision::angles`].
+	pub fn look<'a>(
+		&self,
+		origin: Vec2,
+		heading: f32,
+		enemies: impl Iterator<Item = &'a Enemy> + Clone,
+		space: &Space,
+		obstacles: &[Obstacle],
+		food: &[Food],
+	) -> Vec<RayReading> {
+		let edges: Vec<Segment> = obstacles.iter().flat_map(Obstacle::edges).collect();
+		self.angles(heading)
+			.map(|angle| {
+				let direction = Vec2::new(angle.cos(), angle.sin());
+				let enemy: Option<f32> = enemies
+					.clone()
+					.filter_map(|enemy| {
+						let center: Vec2 = space.nearest(origin, enemy.position.into());
+						ray_circle(origin, direction, center, enemy.radius)
+					})
+					.min_by(f32::total_cmp);
+				let wall: Option<f32> = edges
+					.iter()
+					.filter_map(|edge| ray_segment(origin, direction, edge))
+					.chain((!space.wrap).then(|| ray_bounds(origin, direction, &space.bounds)))
+					.min_by(f32::total_cmp);
+				let food: Option<f32> = food
+					.iter()
+					.filter(|item| self.food && item.eaten_at.is_none())
+					.filter_map(|item| {
+						let center: Vec2 = space.nearest(origin, item.position.into());
+						ray_circle(origin, direction, center, item.radius)
+					})
+					.min_by(f32::total_cmp);
+				RayReading {enemy: self.closeness(enemy), wall: self.closeness(wall), food: self.closeness(food)}
+			})
+			.collect()
+	}
+
+	/// Flattens readings into the input vector for a brain: the enemy and wall closeness of each
+	/// ray, followed by its food closeness when looking for food.
+	pub fn inputs(&self, readings: &[RayReading]) -> Vec<f32> {
+		let per_ray: usize = self.input_len() / self.rays.max(1);
+		readings
+			.iter()
+			.flat_map(|reading| [reading.enemy, reading.wall, reading.food].into_iter().take(per_ray))
+			.collect()
+	}
+
+	/// Turns a hit distance into closeness: `1` when touching, `0` at or beyond the range.
+	fn closeness(&self, distance: Option<f32>) -> f32 {
+		match distance {
+			Some(distance) if distance < self.range => 1. - distance / self.range,
+			_ => 0.,
+		}
+	}
+
+	/// Turns closeness back into the distance along the ray, for drawing.
+	pub fn distance(&self, closeness: f32) -> f32 {
+		(1. - closeness) * self.range
+	}
+}
+
+/// Distance along a ray to where it first touches a circle.
+///
+/// Arguments
+/// * `origin`: where the ray starts.
+/// * `direction`: unit vector the ray travels along.
+/// * `center`, `radius`: the circle.
+///
+/// Returns
+/// * `distance`: `None` if the ray misses, `0` if it starts inside the circle.
+pub fn ray_circle(origin: Vec2, direction: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+	let to_center: Vec2 = center - origin;
+	let along: f32 = to_center.dot(direction);
+	let miss_squared: f32 = to_center.length_squared() - along * along;
+	let radius_squared: f32 = radius * radius;
+	if miss_squared > radius_squared {
+		return None;
+	}
+	let half_chord: f32 = (radius_squared - miss_squared).sqrt();
+	match (along - half_chord, along + half_chord) {
+		(_, exit) if exit < 0. => None, // circle is behind the ray
+		(entry, _) if entry < 0. => Some(0.), // ray starts inside
+		(entry, _) => Some(entry),
+	}
+}
+
+/// Distance along a ray to where it crosses a line segment.
+///
+/// Arguments
+/// * `origin`: where the ray starts.
+/// * `direction`: unit vector the ray travels along.
+/// * `segment`: the segment.
+///
+/// Returns
+/// * `distance`: `None` if the ray misses or runs parallel to the segment.
+pub fn ray_segment(origin: Vec2, direction: Vec2, segment: &Segment) -> Option<f32> {
+	let along: Vec2 = segment.end - segment.start;
+	let denominator: f32 = direction.perp_dot(along);
+	if denominator.abs() <= f32::EPSILON {
+		return None;
+	}
+	let to_start: Vec2 = segment.start - origin;
+	let distance: f32 = to_start.perp_dot(along) / denominator;
+	let t: f32 = to_start.perp_dot(direction) / denominator;
+	(distance >= 0. && (0. ..=1.).contains(&t)).then_some(distance)
+}
+
+/// Distance along a ray from inside the arena to its wall.
+fn ray_bounds(origin: Vec2, direction: Vec2, bounds: &Rect) -> f32 {
+	let x: f32 = match direction.x {
+		dx if dx > 0. => (bounds.right() - origin.x) / dx,
+		dx if dx < 0. => (bounds.left() - origin.x) / dx,
+		_ => f32::INFINITY,
+	};
+	let y: f32 = match direction.y {
+		dy if dy > 0. => (bounds.top() - origin.y) / dy,
+		dy if dy < 0. => (bounds.bottom() - origin.y) / dy,
+		_ => f32::INFINITY,
+	};
+	x.min(y).max(0.)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Asserts that two lists of angles are equal up to rounding.
+	fn assert_close(actual: &[f32], expected: &[f32]) {
+		assert_eq!(actual.len(), expected.len(), "{:?} != {:?}", actual, expected);
+		for (a, b) in actual.iter().zip(expected) {
+			assert!((a - b).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+		}
+	}
+
+	#[test]
+	fn ray_hits_circle_in_front() {
+		let distance = ray_circle(Vec2::ZERO, Vec2::X, Vec2::new(10., 0.), 2.);
+		assert_eq!(distance, Some(8.));
+	}
+
+	#[test]
+	fn ray_misses_circle_to_the_side() {
+		assert_eq!(ray_circle(Vec2::ZERO, Vec2::X, Vec2::new(10., 3.), 2.), None);
+	}
+
+	#[test]
+	fn ray_starting_inside_circle_hits_at_zero() {
+		assert_eq!(ray_circle(Vec2::ZERO, Vec2::X, Vec2::new(1., 0.), 2.), Some(0.));
+	}
+
+	#[test]
+	fn ray_ignores_circle_behind_it() {
+		assert_eq!(ray_circle(Vec2::ZERO, Vec2::X, Vec2::new(-10., 0.), 2.), None);
+	}
+
+	#[test]
+	fn ray_hits_and_misses_segments() {
+		let wall = Segment {start: Vec2::new(5., -1.), end: Vec2::new(5., 1.)};
+		assert_eq!(ray_segment(Vec2::ZERO, Vec2::X, &wall), Some(5.));
+		assert_eq!(ray_segment(Vec2::ZERO, -Vec2::X, &wall), None);
+		assert_eq!(ray_segment(Vec2::ZERO, Vec2::Y, &wall), None);
+	}
+
+	#[test]
+	fn ray_parallel_to_segment_misses() {
+		let wall = Segment {start: Vec2::new(2., 0.), end: Vec2::new(6., 0.)};
+		assert_eq!(ray_segment(Vec2::ZERO, Vec2::X, &wall), None);
+	}
+
+	#[test]
+	fn ray_reaches_each_arena_wall() {
+		let bounds = Rect::from_x_y_w_h(0., 0., 100., 60.);
+		let origin = Vec2::new(10., 5.);
+		let distances: Vec<f32> = [Vec2::X, -Vec2::X, Vec2::Y, -Vec2::Y]
+			.into_iter()
+			.map(|direction| ray_bounds(origin, direction, &bounds))
+			.collect();
+		assert_eq!(distances, vec![40., 60., 25., 35.]);
+	}
+
+	#[test]
+	fn full_circle_spaces_rays_evenly_all_around() {
+		let vision = Vision {rays: 4, fov: TAU, ..Vision::default()};
+		let angles: Vec<f32> = vision.angles(1.).collect();
+		assert_close(&angles, &[1., 1. + TAU / 4., 1. + TAU / 2., 1. + TAU * 3. / 4.]);
+	}
+
+	#[test]
+	fn partial_fov_spans_edge_to_edge_around_heading() {
+		let vision = Vision {rays: 3, fov: TAU / 4., ..Vision::default()};
+		let angles: Vec<f32> = vision.angles(1.).collect();
+		assert_close(&angles, &[1. - TAU / 8., 1., 1. + TAU / 8.]);
+		let single = Vision {rays: 1, fov: TAU / 4., ..Vision::default()};
+		assert_close(&single.angles(1.).collect::<Vec<f32>>(), &[1.]);
+	}
+}
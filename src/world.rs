@@ -1,17 +1,132 @@
 //! The environment, the entities that inhabit it, and the rules of our world are modeled here.
-//! 
+//!
 //! The main entities are the Player and Enemy.
-//! Enemies are hazardous to the player, and will kill them on collision.
+//! Enemies are hazardous to the players, and will kill them on collision.
+//! Any number of players can share a world. They don't interact with each other,
+//! so a whole population can be evaluated against the same enemies at once.
 //! The Player's purpose in life is to float around this environment and avoid death until it cannot.
 //! The Enemy's purpose in life is to wiggle around randomly until the end of time.
+//! Scenarios can also scatter [`Food`] for players to collect. Eating gives a player energy,
+//! which it may need to keep from starving. Players sharing a world compete for the same food,
+//! and take turns at it in a random order every step.
+//! A scenario with a goal turns the world into a navigation task: players start in one spot and
+//! succeed by reaching the goal region, after which they leave the world.
+//!
+//! Entities move by having forces applied to their [`Motion`], which is integrated with the
+//! step's delta time, so the simulation behaves the same at any frame rate.
+//!
+//! The world knows nothing about windows or mice. It owns its arena bounds and random number
+//! generator and is advanced one tick at a time with [`World::step`], so it can be simulated
+//! headless just as well as it can be drawn by a nannou front-end.
+//!
+//! Every random decision (spawning, enemy movement) is drawn from the world's own seeded
+//! generator, so the same seed and the same inputs always produce the same trajectories.
+//!
+//! A player with a brain steers itself: every step what its [`Vision`] sees is fed through its
+//! neural network and the network's outputs are the steering force it applies. Without a brain
+//! it steers towards the input's target.
 
-use nannou::{color::Rgb, rand::random_range, App, Draw};
+use nannou::{
+	color::{Rgb, Rgba},
+	geom::{Rect, Vec2},
+	prelude::TAU,
+	rand::{self, seq::SliceRandom, Rng, SeedableRng},
+	Draw,
+};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	boundary::{Boundary, Space},
+	collision::{circle_circle, Circle},
+	config::{EnemyConfig, PlayerConfig, SpawnConfig, WorldConfig},
+	fitness::{EpisodeStats, NEAR_MISS_GAP},
+	grid::BroadPhase,
+	nn::Network,
+	obstacle::{self, Obstacle, Shape},
+	physics::Motion,
+	vision::{RayReading, Vision},
+};
+
+/// Distance from the input's target at which a player steering towards it starts braking.
+const ARRIVAL_RADIUS: f32 = 50.;
+
+/// Number of values a player's brain produces each step: the horizontal and vertical steering.
+pub const BRAIN_OUTPUTS: usize = 2;
+
+/// Number of times a spawn position is drawn before settling for one where nothing may start.
+const SPAWN_ATTEMPTS: usize = 100;
 
 /// 2D Coordinates of an entity
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Position {pub x: f32, pub y: f32}
 
-/// Keeps track of all entities: the player and their enemies.
-pub struct World {pub player: Player, pub enemies: Vec<Enemy>}
+impl From<Position> for Vec2 {
+	fn from(position: Position) -> Self {
+		Vec2::new(position.x, position.y)
+	}
+}
+
+impl From<Vec2> for Position {
+	fn from(vector: Vec2) -> Self {
+		Position {x: vector.x, y: vector.y}
+	}
+}
+
+/// Keeps track of all entities: the players and their enemies.
+pub struct World {
+	pub players: Vec<Player>,
+	pub enemies: Vec<Enemy>,
+	/// Food items, including those that have been eaten and may come back.
+	pub food: Vec<Food>,
+	/// The arena every entity is confined to.
+	pub bounds: Rect,
+	/// Static shapes that block every entity.
+	pub obstacles: Vec<Obstacle>,
+	/// Number of steps simulated since the world was set up.
+	pub ticks: u64,
+	/// Simulated time in seconds since the world was set up.
+	pub elapsed: f32,
+	/// Seed the world's random number generator was created from.
+	pub seed: u64,
+	/// What the world looks like every time it's set up.
+	pub config: WorldConfig,
+	/// Finds the enemies near a player. Rebuilt every step, right after the enemies move.
+	pub broad_phase: BroadPhase,
+	rng: ChaCha8Rng,
+}
+
+/// Everything a front-end can tell the world during a single step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Input {
+	/// Where the player should move to, if anywhere.
+	pub target: Option<Position>,
+	/// Request to set up a new world once every player has died.
+	pub restart: bool,
+	/// Request to set up a new world right away, even if players are still alive.
+	#[serde(default)]
+	pub reset: bool,
+}
+
+/// Optional extras drawn on top of the world.
+#[derive(Clone, Copy, Debug)]
+pub struct ViewOptions {
+	/// Draw the vision rays of living players up to whatever they hit.
+	pub rays: bool,
+	/// Draw the text overlay with the run's numbers.
+	pub hud: bool,
+	/// Draw the chart of fitness over the generations, when training.
+	pub chart: bool,
+	/// Draw the brain of the selected player.
+	pub network: bool,
+}
+
+impl Default for ViewOptions {
+	/// The HUD, the chart and the selected brain are shown, the rays aren't.
+	fn default() -> Self {
+		Self {rays: false, hud: true, chart: true, network: true}
+	}
+}
 
 /// Plays, learns, and evolves.
 pub struct Player {
@@ -19,16 +134,38 @@ pub struct Player {
 	pub radius: f32,
 	pub color: Rgb,
 	pub alive: bool,
+	pub motion: Motion,
+	/// Direction the player last moved in, in radians. Vision rays fan out around it.
+	pub heading: f32,
+	/// Gained by eating and used up over time. The player starves when it runs out.
+	pub energy: f32,
+	pub vision: Vision,
+	/// What the player's vision reported on the latest step.
+	pub sight: Vec<RayReading>,
+	/// Steers the player when present. Takes [`Vision::input_len`] values and
+	/// produces [`BRAIN_OUTPUTS`] values, which are clamped to `[-1, 1]`.
+	pub brain: Option<Network>,
+	/// What has happened to the player since the world was set up.
+	pub stats: EpisodeStats,
 }
 
 impl Default for Player {
+	/// A player as described by the default [`PlayerConfig`].
 	fn default() -> Self {
-		Self {
-			position: Position {x: 0., y: 0.},
-			radius: 5.0,
-			color: Rgb::new(255.0, 255.0, 255.0),
-			alive: true,
-		}
+		PlayerConfig::default().player()
+	}
+}
+
+impl Player {
+	/// The player's shape, for collision tests.
+	pub fn circle(&self) -> Circle {
+		Circle {center: self.position.into(), radius: self.radius}
+	}
+
+	/// Kills the player, turning it black. Gameplay stops updating it.
+	pub fn kill(&mut self) {
+		self.color = Rgb::new(0.0, 0.0, 0.0);
+		self.alive = false;
 	}
 }
 
@@ -38,15 +175,330 @@ pub struct Enemy {
 	pub radius: f32,
 	pub color: Rgb,
 	pub alive: bool,
+	pub motion: Motion,
 }
 
 impl Default for Enemy {
+	/// An enemy as described by the default [`EnemyConfig`].
 	fn default() -> Self {
-		Self {
-			position: Position {x: 0., y: 0.},
-			radius: 5.0,
-			color: Rgb::new(255.0, 0.0, 0.0),
-			alive: true,
+		EnemyConfig::default().enemy()
+	}
+}
+
+impl Enemy {
+	/// The enemy's shape, for collision tests.
+	pub fn circle(&self) -> Circle {
+		Circle {center: self.position.into(), radius: self.radius}
+	}
+}
+
+/// Something for players to eat.
+pub struct Food {
+	pub position: Position,
+	pub radius: f32,
+	pub color: Rgb,
+	/// Tick on which the item was eaten, `None` while it's there to be eaten.
+	pub eaten_at: Option<u64>,
+}
+
+impl Default for World {
+	/// The default [`WorldConfig`], seeded at random.
+	fn default() -> Self {
+		Self::new(WorldConfig::default(), rand::random())
+	}
+}
+
+impl World {
+	/// Creates an instance of the world struct with a single player.
+	///
+	/// Arguments
+	/// * `config`: the arena and entities to set up.
+	/// * `seed`: seed for every random decision made in the world.
+	///
+	/// Returns
+	/// * `world`: the world struct.
+	pub fn new(config: WorldConfig, seed: u64) -> Self {
+		Self::with_players(config, seed, vec![Player::default()])
+	}
+
+	/// Creates an instance of the world struct shared by the given players.
+	///
+	/// Arguments
+	/// * `config`: the arena and entities to set up.
+	/// * `seed`: seed for every random decision made in the world.
+	/// * `players`: the players to spawn, e.g. one per genome of a generation.
+	///
+	/// Returns
+	/// * `world`: the world struct.
+	pub fn with_players(config: WorldConfig, seed: u64, players: Vec<Player>) -> Self {
+		let mut world = World {
+			players,
+			enemies: Vec::new(),
+			food: Vec::new(),
+			bounds: config.arena.bounds(),
+			obstacles: config.obstacles.clone(),
+			ticks: 0,
+			elapsed: 0.,
+			seed,
+			config,
+			broad_phase: BroadPhase::default(),
+			rng: ChaCha8Rng::seed_from_u64(seed),
+		};
+		world.reset();
+		world
+	}
+
+	/// Puts fresh players at the start, the middle of the arena unless there's a goal, and scatters
+	/// new enemies and food around them.
+	/// The generator is not reseeded, so consecutive resets produce different layouts.
+	/// Players keep their brains and vision.
+	pub fn reset(&mut self) {
+		let World {players, config, ..}: &mut World = self;
+		let players: Vec<Player> = players
+			.iter_mut()
+			.map(|player| Player {
+				position: config.goal.map_or_else(Position::default, |goal| goal.start()),
+				vision: player.vision,
+				brain: player.brain.take(),
+				..config.player.player()
+			})
+			.collect();
+		// every player spawns in the same spot, so keeping enemies away from one keeps them away from all
+		let spawn: Position = players.first().map_or_else(Position::default, |player| player.position);
+		// spawn enemies and scatter them across the environment
+		let World {bounds, obstacles, config, rng, ..}: &mut World = self;
+		// no enemy starts in line with the players along either axis, near the goal or in an obstacle
+		let clearance: f32 = config.spawn.clearance;
+		let enemy_allowed = |position: Position| {
+			(position.x - spawn.x).abs() >= clearance
+				&& (position.y - spawn.y).abs() >= clearance
+				&& !config.goal.is_some_and(|goal| goal.excludes(position))
+				&& !overlaps_obstacle(obstacles, position, config.enemy.radius)
+		};
+		let enemies: Vec<Enemy> = (0..config.enemy_count)
+			.map(|_| Enemy {
+				position: spawn_position(rng, bounds, &config.spawn, enemy_allowed),
+				..config.enemy.enemy()
+			})
+			.collect();
+		let food_allowed = |position: Position| !overlaps_obstacle(obstacles, position, config.food.radius);
+		let food: Vec<Food> = (0..config.food.count)
+			.map(|_| Food {
+				position: spawn_position(rng, bounds, &config.spawn, food_allowed),
+				..config.food.food()
+			})
+			.collect();
+		self.players = players;
+		self.enemies = enemies;
+		self.food = food;
+		self.ticks = 0;
+		self.elapsed = 0.;
+	}
+
+	/// Number of players still alive.
+	pub fn alive(&self) -> usize {
+		self.players.iter().filter(|player| player.alive).count()
+	}
+
+	/// Whether every player has died.
+	pub fn is_over(&self) -> bool {
+		self.alive() == 0
+	}
+
+	/// The arena as the players perceive it, a torus when they wrap around its edges.
+	pub fn space(&self) -> Space {
+		Space {bounds: self.bounds, wrap: self.config.player.boundary == Boundary::Wrap}
+	}
+
+	/// Advances the world by a single tick.
+	/// Enemies move first, then every living player looks at where they are now and moves in
+	/// response. Finally collisions are checked and statistics recorded.
+	///
+	/// Arguments
+	/// * `input`: what the front-end wants to happen this tick.
+	/// * `dt`: time in seconds since the previous step.
+	pub fn step(&mut self, input: &Input, dt: f32) {
+		// Restart
+		if input.reset || (input.restart && self.is_over()) {
+			self.reset();
+		}
+		if !self.is_over() {
+			let previous: Vec<Option<Vec2>> = self.players
+				.iter()
+				.map(|player| player.alive.then(|| player.position.into()))
+				.collect();
+			self.move_enemies(dt);
+			self.broad_phase.rebuild(self.enemies.iter().map(|enemy| (enemy.position.into(), enemy.radius)));
+			self.move_players(input, dt);
+			self.detect_collisions();
+			self.feed_players(dt);
+			self.reach_goal();
+			self.record_stats(&previous);
+			self.ticks += 1;
+			self.elapsed += dt;
+		}
+	}
+
+	/// Makes every enemy wiggle and keeps it out of obstacles and within the world boundary.
+	/// Enemies killed by the boundary are removed.
+	///
+	/// Arguments
+	/// * `dt`: time in seconds since the previous step.
+	fn move_enemies(&mut self, dt: f32) {
+		let World {enemies, bounds, obstacles, config, rng, ..}: &mut World = self;
+		for enemy in enemies.iter_mut() {
+			// make enemies wander by pushing them in a random direction
+			let angle: f32 = random_range(rng, 0., TAU);
+			let force: Vec2 = Vec2::new(angle.cos(), angle.sin()) * enemy.motion.max_force;
+			enemy.motion.apply_force(force);
+			enemy.motion.integrate(&mut enemy.position, dt);
+			obstacle::resolve(obstacles, &mut enemy.position, enemy.radius, &mut enemy.motion.velocity);
+			if config.enemy.boundary.confine(bounds, &mut enemy.position, &mut enemy.motion.velocity) {
+				enemy.alive = false;
+			}
+		}
+		enemies.retain(|enemy| enemy.alive);
+	}
+
+	/// Lets every living player look around and move, then keeps it out of obstacles and within
+	/// the world boundary. Players that touch a lethal obstacle or wall die.
+	///
+	/// Arguments
+	/// * `input`: what the front-end wants to happen this tick.
+	/// * `dt`: time in seconds since the previous step.
+	fn move_players(&mut self, input: &Input, dt: f32) {
+		let space: Space = self.space();
+		let World {players, enemies, food, bounds, obstacles, config, broad_phase, ..}: &mut World = self;
+		for player in players.iter_mut().filter(|player| player.alive) {
+			let previous: Vec2 = player.position.into();
+			let visible = broad_phase
+				.near_in(&space, previous, player.vision.range, enemies.len())
+				.map(|index| &enemies[index]);
+			player.sight = player.vision.look(previous, player.heading, visible, &space, obstacles, food);
+			if let Some(brain) = &player.brain {
+				// let the brain decide how to steer based on what the player sees
+				let steering: Vec<f32> = brain.propagate(&player.vision.inputs(&player.sight));
+				let steering: Vec2 = Vec2::new(steering[0], steering[1]).clamp(Vec2::splat(-1.), Vec2::ONE);
+				let force: Vec2 = steering * player.motion.max_force;
+				player.motion.apply_force(force);
+			} else if let Some(target) = input.target {
+				// Follow Target
+				let force: Vec2 = player.motion.arrive(previous, target.into(), ARRIVAL_RADIUS);
+				player.motion.apply_force(force);
+			}
+			player.motion.integrate(&mut player.position, dt);
+			let Player {position, radius, motion, ..} = player;
+			let lethal: bool = obstacle::resolve(obstacles, position, *radius, &mut motion.velocity);
+			if config.player.boundary.confine(bounds, position, &mut motion.velocity) || lethal {
+				player.kill();
+			}
+			let velocity: Vec2 = player.motion.velocity;
+			if velocity.length_squared() > f32::EPSILON {
+				player.heading = velocity.y.atan2(velocity.x);
+			}
+		}
+	}
+
+	/// Detects enemy collision with each living player.
+	/// If a collision is detected, that player is killed, their color changes to black
+	/// and gameplay stops updating them. The world goes on until every player is dead.
+	pub fn detect_collisions(&mut self) {
+		let space: Space = self.space();
+		let World {players, enemies, broad_phase, ..}: &mut World = self;
+		for player in players.iter_mut().filter(|player| player.alive) {
+			let circle: Circle = player.circle();
+			let collided: bool = broad_phase
+				.near_in(&space, circle.center, circle.radius, enemies.len())
+				.any(|index| {
+					let enemy: &Enemy = &enemies[index];
+					let center: Vec2 = space.nearest(circle.center, enemy.position.into());
+					circle_circle(&circle, &Circle {center, radius: enemy.radius}).is_some()
+				});
+			if collided {
+				player.kill();
+			}
+		}
+	}
+
+	/// Lets every living player eat the food it touches, then uses up its energy for the tick.
+	/// Players that run out of energy starve. Eaten food comes back as the respawn rule says.
+	///
+	/// Arguments
+	/// * `dt`: time in seconds since the previous step.
+	fn feed_players(&mut self, dt: f32) {
+		let space: Space = self.space();
+		let World {players, food, bounds, obstacles, config, ticks, rng, ..}: &mut World = self;
+		// players reach for food in a fresh random order every step, so no one always eats first
+		let mut order: Vec<usize> = (0..players.len()).collect();
+		if !food.is_empty() {
+			order.shuffle(rng);
+		}
+		for index in order {
+			let player: &mut Player = &mut players[index];
+			if !player.alive {
+				continue;
+			}
+			for item in food.iter_mut().filter(|item| item.eaten_at.is_none()) {
+				if space.distance(player.position.into(), item.position.into()) < player.radius + item.radius {
+					item.eaten_at = Some(*ticks);
+					player.energy += config.food.energy;
+					player.stats.food_eaten += 1;
+				}
+			}
+			player.energy -= config.player.energy_drain * dt;
+			if player.energy <= 0. {
+				player.kill();
+			}
+		}
+		let Some(delay) = config.food.respawn.delay() else {
+			return;
+		};
+		for item in food.iter_mut() {
+			if item.eaten_at.is_some_and(|eaten_at| *ticks >= eaten_at + delay) {
+				let radius: f32 = item.radius;
+				let allowed = |position: Position| !overlaps_obstacle(obstacles, position, radius);
+				item.position = spawn_position(rng, bounds, &config.spawn, allowed);
+				item.eaten_at = None;
+			}
+		}
+	}
+
+	/// Tracks how close every living player has come to the goal. Players that reach the goal
+	/// region have succeeded and leave the world, so they stop being updated without dying.
+	fn reach_goal(&mut self) {
+		let space: Space = self.space();
+		let World {players, config, ticks, ..}: &mut World = self;
+		let Some(goal) = config.goal else {
+			return;
+		};
+		let (start, center): (Vec2, Vec2) = (goal.start().into(), goal.center().into());
+		for player in players.iter_mut().filter(|player| player.alive) {
+			let distance: f32 = space.distance(player.position.into(), center);
+			let progress: f32 = space.distance(start, center) - distance;
+			player.stats.goal_progress = player.stats.goal_progress.max(progress);
+			if distance < goal.radius {
+				player.stats.reached_goal = Some(*ticks);
+				player.alive = false;
+			}
+		}
+	}
+
+	/// Records the tick in the episode statistics of every player that was alive at its start.
+	///
+	/// Arguments
+	/// * `previous`: where each player was at the start of the tick, `None` if it was already dead.
+	fn record_stats(&mut self, previous: &[Option<Vec2>]) {
+		let space: Space = self.space();
+		let World {players, enemies, broad_phase, ..}: &mut World = self;
+		for (player, previous) in players.iter_mut().zip(previous) {
+			// a player that died this tick still counts it, one that died earlier has stopped counting
+			if let Some(previous) = previous {
+				let position: Vec2 = player.position.into();
+				let nearby = broad_phase
+					.near_in(&space, position, player.radius + NEAR_MISS_GAP, enemies.len())
+					.map(|index| &enemies[index]);
+				player.stats.record(*previous, position, player.radius, nearby, &space);
+			}
 		}
 	}
 }
@@ -54,148 +506,188 @@ impl Default for Enemy {
 /// Draws entities from the world to the nannou window.
 /// This function is called throughout the program to redraw
 /// each entity's positions, color, etc. as they are updated.
-/// 
+///
 /// Arguments
-/// * `draw`: nannou::draw instance.
+/// * `draw`: nannou::draw instance, in world coordinates.
 /// * `world`: the world struct.
-pub fn draw_view(draw: &Draw, world: &World) {
-	let World {player, enemies}: &World = world;
+/// * `options`: which optional extras to draw.
+pub fn draw_view(draw: &Draw, world: &World, options: &ViewOptions) {
+	let World {players, enemies, food, bounds, obstacles, config, ..}: &World = world;
+	// the arena's edge, which only shows once the camera has zoomed out or panned away
+	draw.rect()
+		.xy(bounds.xy())
+		.wh(bounds.wh())
+		.no_fill()
+		.stroke_weight(1.0)
+		.stroke(Rgba::new(1.0, 1.0, 1.0, 0.3));
+	if let Some(goal) = &config.goal {
+		let color: Rgb = goal.rgb();
+		draw.ellipse()
+			.x_y(goal.start[0], goal.start[1])
+			.radius(goal.clearance)
+			.no_fill()
+			.stroke_weight(1.0)
+			.stroke(Rgba::new(color.red, color.green, color.blue, 0.3));
+		draw.ellipse()
+			.x_y(goal.center[0], goal.center[1])
+			.radius(goal.radius)
+			.color(Rgba::new(color.red, color.green, color.blue, 0.35))
+			.stroke_weight(1.5)
+			.stroke(color);
+	}
+	for obstacle in obstacles.iter() {
+		draw_obstacle(draw, obstacle);
+	}
 	let quick_draw = |position: &Position, &radius, &color| {
 		draw.ellipse()
 			.x_y(position.x, position.y)
 			.radius(radius)
 			.color(color);
 	};
-	quick_draw(&player.position, &player.radius, &player.color);
-    for enemy in enemies.iter() {
+	if options.rays {
+		for player in players.iter().filter(|player| player.alive) {
+			draw_rays(draw, player);
+		}
+	}
+	for item in food.iter().filter(|item| item.eaten_at.is_none()) {
+		quick_draw(&item.position, &item.radius, &item.color);
+	}
+	for player in players.iter() {
+		quick_draw(&player.position, &player.radius, &player.color);
+	}
+	for enemy in enemies.iter() {
 		quick_draw(&enemy.position, &enemy.radius, &enemy.color);
-    }
-}
-
-pub fn update(app: &App, world: &mut World) {
-	controls(app, world);
-	if world.player.alive {
-		gameplay(app, world);
-		detect_collisions(world);
-		handle_bounds(app, world);
 	}
 }
 
-/// Creates an instance of the world struct.
-/// 
-/// Returns
-/// * `world`: the world struct.
-pub fn setup_world() -> World {
-	let player: Player = Player::default();
-	// spawn enemies and scatter them across the environment
-	let num_enemies: i32 = 500;
-    let enemies: Vec<Enemy> = (0..num_enemies)
-        .map(|_| Enemy {position: enemy_spawn_position(&player), ..Default::default()})
-        .collect();
-	World {player, enemies}
-}
-
-/// User inputs to control world attributes
-/// 
+/// Draws an obstacle, lethal ones in orange and the rest in gray.
+///
 /// Arguments
-/// * `app`: nannou::app instance.
-/// * `world`: the world struct.
-pub fn controls(app: &App, world: &mut World) {
-	// Left Click: Restart
-	if app.mouse.buttons.left().is_down() && !world.player.alive {
-		*world = setup_world();
+/// * `draw`: nannou::draw instance.
+/// * `obstacle`: the obstacle to draw.
+fn draw_obstacle(draw: &Draw, obstacle: &Obstacle) {
+	let color: Rgba = match obstacle.lethal {
+		true => Rgba::new(1.0, 0.5, 0.1, 0.9),
+		false => Rgba::new(0.6, 0.6, 0.65, 0.9),
+	};
+	match &obstacle.shape {
+		Shape::Rect {center, size} => {
+			draw.rect()
+				.x_y(center[0], center[1])
+				.w_h(size[0], size[1])
+				.color(color);
+		}
+		Shape::Segment {start, end} => {
+			draw.line()
+				.start(Vec2::from(*start))
+				.end(Vec2::from(*end))
+				.weight(2.0)
+				.color(color);
+		}
+		Shape::Polygon {points} => {
+			draw.polygon()
+				.points(points.iter().map(|point| Vec2::from(*point)))
+				.color(color);
+		}
 	}
 }
 
-/// Handles actions that should happen while in-game.
-/// 
+/// Draws each of the player's vision rays up to the nearest thing it hit.
+/// Rays that see an enemy are red, rays that see food are green, rays that only see the wall
+/// are yellow, and rays that see nothing are faint white.
+///
 /// Arguments
-/// * `app`: nannou::app instance.
-/// * `world`: the world struct.
-pub fn gameplay(app: &App, world: &mut World) {
-	let World {player, enemies}: &mut World = world;
-    player.position = Position {x: app.mouse.x, y: app.mouse.y}; // Follow Mouse
-    for enemy in enemies.iter_mut() {
-		// make enemies move randomly in any direction
-        enemy.position.x = random_range(enemy.position.x - 1., enemy.position.x + 1.) ;
-        enemy.position.y = random_range(enemy.position.y - 1., enemy.position.y + 1.) ;
+/// * `draw`: nannou::draw instance.
+/// * `player`: the player whose vision is drawn.
+fn draw_rays(draw: &Draw, player: &Player) {
+	let origin: Vec2 = player.position.into();
+	for (angle, reading) in player.vision.angles(player.heading).zip(&player.sight) {
+		let closeness: f32 = reading.enemy.max(reading.wall).max(reading.food);
+		let color: Rgba = match reading {
+			RayReading {enemy, ..} if *enemy > 0. && *enemy >= closeness => Rgba::new(1.0, 0.3, 0.3, 0.8),
+			RayReading {food, ..} if *food > 0. && *food >= closeness => Rgba::new(0.3, 0.9, 0.4, 0.8),
+			RayReading {wall, ..} if *wall > 0. => Rgba::new(1.0, 1.0, 0.3, 0.5),
+			_ => Rgba::new(1.0, 1.0, 1.0, 0.2),
+		};
+		let end: Vec2 = origin + Vec2::new(angle.cos(), angle.sin()) * player.vision.distance(closeness);
+		draw.line()
+			.start(origin)
+			.end(end)
+			.weight(1.0)
+			.color(color);
 	}
 }
 
-/// Detects enemy collision with a player.
-/// If a collision is detected, the player is killed, their color changes to black
-/// and gameplay stops updating.
+/// Creates a random position anywhere in the spawn area, drawing again while it isn't allowed.
+/// After [`SPAWN_ATTEMPTS`] draws the last one is used anyway.
 ///
 /// Arguments
-/// * `world`: the world struct.
-fn detect_collisions(world: &mut World) {
-	let World {player, enemies}: &mut World = world;
-	for enemy in enemies.iter_mut() {
-		let radius: f32 = player.radius + enemy.radius; // collision distance
-		let x: f32 = (player.position.x - enemy.position.x).abs(); // actual x distance
-		let y: f32 = (player.position.y - enemy.position.y).abs(); // actual y distance
-		if x < radius && y < radius { // Collision detected
-			player.color = Rgb::new(0.0, 0.0, 0.0);
-			player.alive = false;
-			// note: maybe modify a game struct here in the future
+/// * `rng`: the world's random number generator.
+/// * `bounds`: the arena rectangle.
+/// * `spawn`: how much of the arena to use.
+/// * `allowed`: whether an entity may start at a position.
+///
+/// Returns
+/// * `position`: a random position
+fn spawn_position(
+	rng: &mut impl Rng,
+	bounds: &Rect,
+	spawn: &SpawnConfig,
+	allowed: impl Fn(Position) -> bool,
+) -> Position {
+	let (width, height) = (bounds.w() / 2. * spawn.area, bounds.h() / 2. * spawn.area);
+	let mut position = Position::default();
+	for _ in 0..SPAWN_ATTEMPTS {
+		position = Position {
+			x: random_range(rng, bounds.x() - width, bounds.x() + width),
+			y: random_range(rng, bounds.y() - height, bounds.y() + height),
+		};
+		if allowed(position) {
+			break;
 		}
 	}
+	position
 }
 
-/// Handles which bounds affect which entities.
-///
-/// Arguments
-/// * `app`: nannou::app instance.
-/// * `world`: the world struct.
-fn handle_bounds(app: &App, world: &mut World) {
-	let World {player, enemies}: &mut World = world;
-	// both player and enemies are affected by the world boundary
-	world_boundary(app, &mut player.position);
-	for enemy in enemies.iter_mut() {
-		world_boundary(app, &mut enemy.position);
-    }
+/// Whether a circle at the given position would overlap any obstacle.
+fn overlaps_obstacle(obstacles: &[Obstacle], position: Position, radius: f32) -> bool {
+	let circle = Circle {center: position.into(), radius};
+	obstacles.iter().any(|obstacle| obstacle.contact(&circle).is_some())
 }
 
-/// Basic world boundary. This prevents all entities from moving beyond the window.
-/// 
-/// Arguments
-/// * `app`: nannou::app instance.
-/// * `position`: the 2D position struct.
-fn world_boundary(app: &App, position: &mut Position) {
-	if position.y > app.window_rect().top() {
-		position.y = app.window_rect().top();
+/// Generates a random value within `[min, max)` from the given generator.
+/// Like `nannou::rand::random_range`, the bounds are swapped if `min` is greater than `max`.
+fn random_range(rng: &mut impl Rng, min: f32, max: f32) -> f32 {
+	let (min, max) = if min <= max { (min, max) } else { (max, min) };
+	if min == max {
+		return min;
 	}
-	if position.y < app.window_rect().bottom() {
-		position.y = app.window_rect().bottom();
+	rng.gen_range(min..max)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Steps a world with a player chasing a fixed target and returns where everything ended up.
+	fn run(seed: u64) -> Vec<Position> {
+		let config = WorldConfig {enemy_count: 50, ..WorldConfig::default()};
+		let mut world = World::new(config, seed);
+		let input = Input {target: Some(Position {x: 100., y: -50.}), ..Input::default()};
+		for _ in 0..300 {
+			world.step(&input, 1. / 60.);
+		}
+		let players = world.players.iter().map(|player| player.position);
+		players.chain(world.enemies.iter().map(|enemy| enemy.position)).collect()
 	}
-	if position.x < app.window_rect().left() {
-		position.x = app.window_rect().left();
+
+	#[test]
+	fn same_seed_and_inputs_give_same_positions() {
+		assert_eq!(run(7), run(7));
 	}
-	if position.x > app.window_rect().right() {
-		position.x = app.window_rect().right();
+
+	#[test]
+	fn different_seeds_give_different_positions() {
+		assert_ne!(run(7), run(8));
 	}
 }
-
-/// Creates a random position with a minimum distance from the player.
-/// By default, no enemy will spawn within 2x the player's radius.
-/// 
-/// Arguments
-/// * `player`: the main player struct.
-/// Returns
-/// * `position`: a random position
-
-fn enemy_spawn_position(player: &Player) -> Position {
-	let Player {position, radius, ..} = player;
-	let radius = radius * 2.;
-	let size = 512. / 2. * 0.80;
-	Position {
-		x: match random_range(0., 1.) > 0.5 {
-			true => random_range(-size, position.x - radius),
-			false => random_range(position.x + radius, size)
-		},
-		y: match random_range(0., 1.) > 0.5 {
-			true => random_range(-size, position.y - radius),
-			false => random_range(position.y + radius, size)
-		}
-	}
-}
\ No newline at end of file